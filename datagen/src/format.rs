@@ -0,0 +1,175 @@
+//! The sample file format. All integers are little-endian.
+//!
+//! The file starts with a header:
+//! - the magic bytes `MBTD`
+//! - the format version, one byte, currently 1
+//! - the board width and height, one byte each
+//! - the number of planes, two bytes
+//!
+//! Followed by any number of samples:
+//! - the planes, in the order of the [Layout](mb_goban::Layout), packed 8 values to a byte,
+//!   least significant bit first
+//! - the next move as two bytes: `y * width + x`, or `width * height` for a pass
+//! - the result as one signed byte
+
+use std::io::{self, Read, Write};
+
+use crate::Sample;
+
+const MAGIC: &[u8; 4] = b"MBTD";
+const VERSION: u8 = 1;
+
+/// Writes samples of a fixed board size and number of planes.
+pub struct SampleWriter<W: Write> {
+    output: W,
+    size: (usize, usize),
+    planes: usize,
+}
+impl<W: Write> SampleWriter<W> {
+    /// Write the header to `output`.
+    pub fn new(mut output: W, size: (usize, usize), planes: usize) -> io::Result<Self> {
+        let (w, h) = size;
+
+        if w > u8::MAX as usize || h > u8::MAX as usize || planes > u16::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "board or layout too large",
+            ));
+        }
+
+        output.write_all(MAGIC)?;
+        output.write_all(&[VERSION, w as u8, h as u8])?;
+        output.write_all(&(planes as u16).to_le_bytes())?;
+
+        Ok(Self {
+            output,
+            size,
+            planes,
+        })
+    }
+
+    pub fn write(&mut self, sample: &Sample) -> io::Result<()> {
+        let (w, h) = self.size;
+
+        if sample.planes.len() != self.planes * w * h {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "sample does not match the header",
+            ));
+        }
+
+        let mut packed = vec![0u8; sample.planes.len().div_ceil(8)];
+
+        for (i, &v) in sample.planes.iter().enumerate() {
+            packed[i / 8] |= (v & 1) << (i % 8);
+        }
+
+        let next_move = match sample.next_move {
+            Some((x, y)) => y * w + x,
+            None => w * h,
+        };
+
+        self.output.write_all(&packed)?;
+        self.output.write_all(&(next_move as u16).to_le_bytes())?;
+        self.output.write_all(&sample.result.to_le_bytes())?;
+
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+/// Read a whole sample file, returning the board (width, height) and the samples.
+pub fn read_samples<R: Read>(mut input: R) -> io::Result<((usize, usize), Vec<Sample>)> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let mut header = [0u8; 9];
+    input.read_exact(&mut header)?;
+
+    if &header[0..4] != MAGIC || header[4] != VERSION {
+        return Err(invalid("not a version 1 sample file"));
+    }
+
+    let (w, h) = (header[5] as usize, header[6] as usize);
+    let planes = u16::from_le_bytes([header[7], header[8]]) as usize;
+
+    let values = planes * w * h;
+    let mut record = vec![0u8; values.div_ceil(8) + 3];
+
+    let mut data = Vec::new();
+    input.read_to_end(&mut data)?;
+
+    if data.len() % record.len() != 0 {
+        return Err(invalid("truncated sample"));
+    }
+
+    let mut samples = Vec::new();
+
+    for chunk in data.chunks(record.len()) {
+        record.copy_from_slice(chunk);
+
+        let n = values.div_ceil(8);
+        let next_move = u16::from_le_bytes([record[n], record[n + 1]]) as usize;
+
+        samples.push(Sample {
+            planes: (0..values)
+                .map(|i| (record[i / 8] >> (i % 8)) & 1)
+                .collect(),
+            next_move: match next_move == w * h {
+                true => None,
+                false => Some((next_move % w, next_move / w)),
+            },
+            result: record[n + 2] as i8,
+        });
+    }
+
+    return Ok(((w, h), samples));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let samples = vec![
+            Sample {
+                planes: vec![1, 0, 0, 1, 1, 0, 1, 0, 0, 1, 1, 1],
+                next_move: Some((2, 1)),
+                result: -1,
+            },
+            Sample {
+                planes: vec![0; 12],
+                next_move: None,
+                result: 1,
+            },
+        ];
+
+        let mut writer = SampleWriter::new(Vec::new(), (3, 2), 2).unwrap();
+
+        for s in &samples {
+            writer.write(s).unwrap();
+        }
+
+        let bytes = writer.into_inner();
+
+        // 9 bytes of header, then 2 bytes of planes, 2 of move and 1 of result per sample.
+        assert_eq!(bytes.len(), 9 + 2 * 5);
+        assert_eq!(read_samples(&bytes[..]).unwrap(), ((3, 2), samples));
+    }
+
+    #[test]
+    fn wrong_length() {
+        let mut writer = SampleWriter::new(Vec::new(), (3, 2), 2).unwrap();
+
+        let sample = Sample {
+            planes: vec![0; 6],
+            next_move: None,
+            result: 0,
+        };
+
+        assert!(writer.write(&sample).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+#![allow(clippy::needless_return)]
+
+//! Turn collections of sgf files into neural network training samples.
+//!
+//! Each sample is the [feature planes](mb_goban::Layout) of a position on a game's main line,
+//! the move that was played from it, and the final result of the game.
+//! Samples are written in the format described in [format].
+
+pub mod format;
+mod record;
+mod symmetry;
+
+pub use format::{read_samples, SampleWriter};
+pub use record::{Event, Record, SkipReason};
+pub use symmetry::Symmetry;
+
+/// A training sample.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sample {
+    /// One value of 0 or 1 per plane and point.
+    pub planes: Vec<u8>,
+    /// The move played from the position, [None] for a pass.
+    pub next_move: Option<(usize, usize)>,
+    /// 1 if black won, -1 if white won, 0 for draws and unknown results.
+    pub result: i8,
+}
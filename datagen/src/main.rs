@@ -0,0 +1,152 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use mb_datagen::{Record, SampleWriter, Symmetry};
+use mb_goban::Layout;
+
+const USAGE: &str =
+    "usage: mb_datagen <sgf directory> <output file> [--size N] [--history N] [--augment]";
+
+struct Options {
+    input: PathBuf,
+    output: PathBuf,
+    size: usize,
+    history: usize,
+    augment: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut paths = Vec::new();
+    let mut size = 19;
+    let mut history = 8;
+    let mut augment = false;
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" | "--history" => {
+                let n = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or(format!("{arg} needs a number"))?;
+
+                match arg.as_str() {
+                    "--size" => size = n,
+                    _ => history = n,
+                }
+            }
+            "--augment" => augment = true,
+
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let [input, output] = <[PathBuf; 2]>::try_from(paths).map_err(|_| String::from(USAGE))?;
+
+    Ok(Options {
+        input,
+        output,
+        size,
+        history,
+        augment,
+    })
+}
+
+/// Every .sgf file under `dir`, sorted so that the output is reproducible.
+fn sgf_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            files.extend(sgf_files(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("sgf"))
+        {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+fn run(options: Options) -> io::Result<()> {
+    let size = (options.size, options.size);
+    let layout = Layout::standard(options.history);
+
+    let symmetries = match options.augment {
+        true => Symmetry::all(size),
+        false => vec![Symmetry::default()],
+    };
+
+    let output = BufWriter::new(File::create(&options.output)?);
+    let mut writer = SampleWriter::new(output, size, layout.planes.len())?;
+
+    let (mut games, mut skipped, mut samples) = (0, 0, 0);
+
+    for path in sgf_files(&options.input)? {
+        let text = match fs::read_to_string(&path) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("skipping {}: {e}", path.display());
+                skipped += 1;
+                continue;
+            }
+        };
+
+        let game_samples = Record::from_sgf(&text).and_then(|record| {
+            if record.size != size {
+                return Err(mb_datagen::SkipReason::BoardSize(record.size));
+            }
+
+            let mut all = Vec::new();
+
+            for &s in &symmetries {
+                all.extend(record.samples(&layout, s)?);
+            }
+
+            Ok(all)
+        });
+
+        match game_samples {
+            Ok(game_samples) => {
+                for s in &game_samples {
+                    writer.write(s)?;
+                }
+
+                games += 1;
+                samples += game_samples.len();
+            }
+
+            Err(reason) => {
+                eprintln!("skipping {}: {reason}", path.display());
+                skipped += 1;
+            }
+        }
+    }
+
+    writer.into_inner().into_inner()?;
+
+    eprintln!("{samples} samples from {games} games, {skipped} games skipped");
+
+    Ok(())
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(2);
+    });
+
+    if let Err(e) = run(options) {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use mb_goban::{Board, Error, Layout, Rules, Stone};
+use mb_sgf::util::{format_coords, parse_board_size, parse_move, parse_point_list};
+use mb_sgf::{ParseError, SgfTree};
+
+use crate::{Sample, Symmetry};
+
+/// Rules lenient enough for games played under any rule set.
+const RULES: Rules = Rules {
+    suicide_allowed: true,
+    superko: false,
+};
+
+/// Something that happens on the main line of a game.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Event {
+    /// A stone added or removed with AB[], AW[] or AE[].
+    Setup(Stone, (usize, usize)),
+    /// A move by B[] or W[]. [None] is a pass.
+    Move(Stone, Option<(usize, usize)>),
+}
+
+/// Why a game was left out of the training data.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SkipReason {
+    Parse(ParseError),
+    BoardSize((usize, usize)),
+    InvalidCoordinates(String),
+    NoMoves,
+    /// Move `number`, counting from 1, could not be played.
+    IllegalMove {
+        number: usize,
+        error: Error,
+    },
+}
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "invalid sgf: {e}"),
+            Self::BoardSize((w, h)) => write!(f, "unsupported board size {w}x{h}"),
+            Self::InvalidCoordinates(s) => write!(f, "invalid coordinates [{s}]"),
+            Self::NoMoves => write!(f, "no moves"),
+            Self::IllegalMove { number, error } => write!(f, "move {number} is illegal: {error:?}"),
+        }
+    }
+}
+
+/// The main line of a game.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Record {
+    pub size: (usize, usize),
+    pub events: Vec<Event>,
+    /// 1 if black won, -1 if white won, 0 for draws and unknown results.
+    pub result: i8,
+}
+impl Record {
+    pub fn from_sgf(text: &str) -> Result<Self, SkipReason> {
+        let tree = mb_sgf::parse(text).map_err(SkipReason::Parse)?;
+
+        Self::from_tree(&tree)
+    }
+
+    pub fn from_tree(tree: &SgfTree) -> Result<Self, SkipReason> {
+        let mut record = Record {
+            size: (19, 19),
+            events: Vec::new(),
+            result: 0,
+        };
+
+        let invalid = |v: &str| SkipReason::InvalidCoordinates(v.into());
+
+        for prop in tree.main_line().flat_map(|n| &n.props) {
+            let value = prop.values.first().map(String::as_str).unwrap_or_default();
+
+            match prop.id.as_str() {
+                "SZ" => {
+                    record.size = parse_board_size(value).map_err(SkipReason::Parse)?;
+                }
+                "RE" => {
+                    record.result = match value.chars().next() {
+                        Some('B') => 1,
+                        Some('W') => -1,
+                        _ => 0,
+                    };
+                }
+
+                "AB" | "AW" | "AE" => {
+                    let color = match prop.id.as_str() {
+                        "AB" => Stone::Black,
+                        "AW" => Stone::White,
+                        _ => Stone::Empty,
+                    };
+
+                    for v in &prop.values {
+                        for p in parse_point_list(&[v]).map_err(|_| invalid(v))? {
+                            record.events.push(Event::Setup(color, p));
+                        }
+                    }
+                }
+                "B" | "W" => {
+                    let color = match prop.id.as_str() {
+                        "B" => Stone::Black,
+                        _ => Stone::White,
+                    };
+
+                    let point = parse_move(value, record.size).map_err(|_| invalid(value))?;
+
+                    record.events.push(Event::Move(color, point));
+                }
+
+                _ => {}
+            }
+        }
+
+        let (w, h) = record.size;
+
+        if w == 0 || h == 0 || w > 52 || h > 52 {
+            return Err(SkipReason::BoardSize(record.size));
+        }
+
+        for event in &record.events {
+            if let Event::Setup(_, p) | Event::Move(_, Some(p)) = *event {
+                if p.0 >= w || p.1 >= h {
+                    return Err(SkipReason::InvalidCoordinates(format_coords(p)));
+                }
+            }
+        }
+
+        if !record.events.iter().any(|e| matches!(e, Event::Move(..))) {
+            return Err(SkipReason::NoMoves);
+        }
+
+        return Ok(record);
+    }
+
+    /// Replay the game as seen through `symmetry`, taking a sample before each move.
+    pub fn samples(&self, layout: &Layout, symmetry: Symmetry) -> Result<Vec<Sample>, SkipReason> {
+        let mut board = Board::empty(self.size.0, self.size.1);
+        let mut samples = Vec::new();
+        let mut number = 0;
+
+        let transformed_size = match symmetry.transpose {
+            true => (self.size.1, self.size.0),
+            false => self.size,
+        };
+        let mut board_t = Board::empty(transformed_size.0, transformed_size.1);
+
+        for event in &self.events {
+            match *event {
+                Event::Setup(color, p) => {
+                    let q = symmetry.apply(p, self.size);
+
+                    board
+                        .setup(&[(p, color)])
+                        .and_then(|_| board_t.setup(&[(q, color)]))
+                        .map_err(|error| SkipReason::IllegalMove { number, error })?;
+                }
+
+                Event::Move(color, p) => {
+                    number += 1;
+
+                    let q = p.map(|p| symmetry.apply(p, self.size));
+
+                    samples.push(Sample {
+                        planes: layout.to_u8(&board_t, color),
+                        next_move: q,
+                        result: self.result,
+                    });
+
+                    match (p, q) {
+                        (Some(p), Some(q)) => {
+                            board
+                                .play(p.0, p.1, color, &RULES)
+                                .map_err(|error| SkipReason::IllegalMove { number, error })?;
+                            // Symmetric positions are equally legal.
+                            board_t.play(q.0, q.1, color, &RULES).unwrap();
+                        }
+                        _ => {
+                            board.pass();
+                            board_t.pass();
+                        }
+                    }
+                }
+            }
+        }
+
+        return Ok(samples);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mb_goban::{IllegalMove, Plane};
+
+    #[test]
+    fn main_line() {
+        let record = Record::from_sgf("(;SZ[9]RE[W+R]AB[aa];W[bb];B[tt](;B[cc])(;B[dd]))").unwrap();
+
+        assert_eq!(record.size, (9, 9));
+        assert_eq!(record.result, -1);
+        assert_eq!(
+            record.events,
+            [
+                Event::Setup(Stone::Black, (0, 0)),
+                Event::Move(Stone::White, Some((1, 1))),
+                Event::Move(Stone::Black, None),
+                Event::Move(Stone::Black, Some((2, 2))),
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_reasons() {
+        assert!(matches!(
+            Record::from_sgf("(;SZ[9];B[aa]"),
+            Err(SkipReason::Parse(_))
+        ));
+        assert_eq!(Record::from_sgf("(;SZ[9]AB[aa])"), Err(SkipReason::NoMoves));
+        assert_eq!(
+            Record::from_sgf("(;SZ[99];B[aa])"),
+            Err(SkipReason::BoardSize((99, 99)))
+        );
+        assert_eq!(
+            Record::from_sgf("(;SZ[9];B[jj])"),
+            Err(SkipReason::InvalidCoordinates(String::from("jj")))
+        );
+        // Coordinates that aren't letters are already a parse error.
+        assert!(matches!(
+            Record::from_sgf("(;SZ[9];B[a1])"),
+            Err(SkipReason::Parse(_))
+        ));
+
+        let record = Record::from_sgf("(;SZ[9];B[aa];W[aa])").unwrap();
+
+        assert_eq!(
+            record.samples(&Layout::standard(1), Symmetry::default()),
+            Err(SkipReason::IllegalMove {
+                number: 2,
+                error: Error::IllegalMove(IllegalMove::NonEmptySpace),
+            })
+        );
+    }
+
+    #[test]
+    fn history_planes() {
+        // A handicap stone, then a pass between two white moves.
+        let record = Record::from_sgf("(;SZ[3]AB[aa];W[cc];B[];W[bb])").unwrap();
+        let layout = Layout::new(vec![Plane::LastMove(0), Plane::LastMove(1)]);
+
+        let samples = record.samples(&layout, Symmetry::default()).unwrap();
+        let planes: Vec<&[u8]> = samples.iter().map(|s| s.planes.as_slice()).collect();
+
+        #[rustfmt::skip]
+        assert_eq!(
+            planes,
+            [
+                // The handicap stone is not a move.
+                &[0, 0, 0, 0, 0, 0, 0, 0, 0, /**/ 0, 0, 0, 0, 0, 0, 0, 0, 0][..],
+                &[0, 0, 0, 0, 0, 0, 0, 0, 1, /**/ 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                // The pass is the last move.
+                &[0, 0, 0, 0, 0, 0, 0, 0, 0, /**/ 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            ]
+        );
+    }
+
+    #[test]
+    fn symmetric_samples() {
+        let record = Record::from_sgf("(;SZ[3]RE[B+3.5];B[aa];W[cb])").unwrap();
+        let layout = Layout::new(vec![Plane::Stones(Stone::Black)]);
+
+        let symmetry = Symmetry {
+            transpose: true,
+            flip_x: true,
+            flip_y: false,
+        };
+
+        let samples = record.samples(&layout, symmetry).unwrap();
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].next_move, Some((2, 0)));
+        assert_eq!(samples[1].next_move, Some((1, 2)));
+        assert_eq!(samples[1].planes, [0, 0, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(samples[1].result, 1);
+    }
+}
@@ -0,0 +1,75 @@
+/// One of the 8 symmetries of a square board: an optional transposition followed by optional flips.
+/// Rectangular boards only have the 4 symmetries without transposition.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Symmetry {
+    pub transpose: bool,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+impl Symmetry {
+    /// Every symmetry of a board of the given (width, height), starting with the identity.
+    pub fn all(size: (usize, usize)) -> Vec<Self> {
+        let transposes: &[bool] = match size.0 == size.1 {
+            true => &[false, true],
+            false => &[false],
+        };
+
+        let mut all = Vec::new();
+
+        for &transpose in transposes {
+            for flip_x in [false, true] {
+                for flip_y in [false, true] {
+                    all.push(Self {
+                        transpose,
+                        flip_x,
+                        flip_y,
+                    });
+                }
+            }
+        }
+
+        return all;
+    }
+
+    /// Where `p` ends up on a board of the given (width, height).
+    pub fn apply(&self, p: (usize, usize), size: (usize, usize)) -> (usize, usize) {
+        let (mut x, mut y) = p;
+        let (mut w, mut h) = size;
+
+        if self.transpose {
+            (x, y) = (y, x);
+            (w, h) = (h, w);
+        }
+        if self.flip_x {
+            x = w - 1 - x;
+        }
+        if self.flip_y {
+            y = h - 1 - y;
+        }
+
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetries() {
+        let square = Symmetry::all((19, 19));
+        let rectangle = Symmetry::all((9, 13));
+
+        assert_eq!(square.len(), 8);
+        assert_eq!(rectangle.len(), 4);
+
+        // Every symmetry maps a corner-ish point somewhere different.
+        let mut images: Vec<_> = square.iter().map(|s| s.apply((2, 3), (19, 19))).collect();
+        images.sort();
+        images.dedup();
+
+        assert_eq!(images.len(), 8);
+        assert_eq!(square[0].apply((2, 3), (19, 19)), (2, 3));
+        assert_eq!(rectangle[3].apply((2, 3), (9, 13)), (6, 9));
+    }
+}
@@ -0,0 +1,71 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use mb_goban::{Board, Rules, Stone};
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Play(u8, u8, bool),
+    Place(u8, u8, Option<bool>),
+    Undo,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    width: u8,
+    height: u8,
+    suicide_allowed: bool,
+    superko: bool,
+    ops: Vec<Op>,
+}
+
+fn color(black: bool) -> Stone {
+    if black {
+        Stone::Black
+    } else {
+        Stone::White
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let (w, h) = (input.width as usize % 20, input.height as usize % 20);
+    let rules = Rules {
+        suicide_allowed: input.suicide_allowed,
+        superko: input.superko,
+    };
+
+    let mut board = Board::empty(w, h);
+
+    for op in input.ops {
+        let before = board.clone();
+
+        let result = match op {
+            // Coordinates are allowed to go slightly out of bounds to exercise the errors.
+            Op::Play(x, y, black) => board
+                .play(x as usize % 21, y as usize % 21, color(black), &rules)
+                .map(|_| ()),
+            Op::Place(x, y, s) => board
+                .place(
+                    x as usize % 21,
+                    y as usize % 21,
+                    s.map(color).unwrap_or(Stone::Empty),
+                    &rules,
+                )
+                .map(|_| ()),
+            Op::Undo => board.undo(),
+        };
+
+        if result.is_err() {
+            assert!(board == before);
+        }
+
+        for y in 0..h {
+            for x in 0..w {
+                let g = board.get_group(x, y).unwrap();
+
+                assert!(g.color == Stone::Empty || !g.liberties.is_empty());
+            }
+        }
+    }
+});
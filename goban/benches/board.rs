@@ -0,0 +1,75 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use mb_goban::{perft, Board, Rules, Stone};
+
+/// A board filled with a fixed pattern of moves, with a few captures along the way.
+fn filled_board(size: usize) -> Board {
+    let mut board = Board::empty(size, size);
+    let mut turn = Stone::Black;
+
+    for i in 0..size * size {
+        let (x, y) = ((i * 7) % size, (i * 7 / size + i) % size);
+
+        if board.play(x, y, turn, &Rules::JAPANESE).is_ok() {
+            turn = !turn;
+        }
+    }
+
+    board
+}
+
+fn play(c: &mut Criterion) {
+    for size in [9, 19] {
+        c.bench_function(&format!("play {size}x{size}"), |b| {
+            b.iter(|| filled_board(black_box(size)))
+        });
+    }
+}
+
+fn get_group(c: &mut Criterion) {
+    for size in [9, 19] {
+        let mut board = Board::empty(size, size);
+
+        // One long snake of black stones, covering every other row
+        for y in (0..size).step_by(2) {
+            for x in 0..size {
+                board.play(x, y, Stone::Black, &Rules::JAPANESE).unwrap();
+            }
+
+            let x = if y % 4 == 0 { size - 1 } else { 0 };
+
+            if y + 1 < size {
+                board
+                    .play(x, y + 1, Stone::Black, &Rules::JAPANESE)
+                    .unwrap();
+            }
+        }
+
+        c.bench_function(&format!("get_group {size}x{size}"), |b| {
+            b.iter(|| board.get_group(black_box(0), black_box(0)).unwrap())
+        });
+    }
+}
+
+fn hash(c: &mut Criterion) {
+    for size in [9, 19] {
+        let board = filled_board(size);
+
+        c.bench_function(&format!("position_hash {size}x{size}"), |b| {
+            b.iter(|| black_box(&board).position_hash())
+        });
+    }
+}
+
+fn perft_bench(c: &mut Criterion) {
+    for size in [9, 19] {
+        let board = Board::empty(size, size);
+
+        c.bench_function(&format!("perft 2 {size}x{size}"), |b| {
+            b.iter(|| perft(black_box(&board), &Rules::JAPANESE, Stone::Black, 2))
+        });
+    }
+}
+
+criterion_group!(benches, play, get_group, hash, perft_bench);
+criterion_main!(benches);
@@ -1,7 +1,12 @@
-use std::collections::HashSet;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+use rustc_hash::FxHasher;
 
 use crate::rule::{IllegalMove, Rules};
-use crate::{Error, Result};
+use crate::topology::{Grid, Topology};
+use crate::{Error, HashSet, Result};
 
 /// Represents a point on a [Board]
 #[derive(Clone, Copy, PartialEq, Debug, Hash)]
@@ -11,7 +16,7 @@ pub enum Stone {
     Black,
     White,
 }
-impl std::ops::Not for Stone {
+impl core::ops::Not for Stone {
     type Output = Self;
 
     fn not(self) -> Self::Output {
@@ -23,36 +28,50 @@ impl std::ops::Not for Stone {
     }
 }
 
-/// Represents the current state of a go game
+/// Represents the current state of a go game.
+///
+/// The shape of the board is given by its [Topology], which is a rectangular [Grid] by default.
 #[derive(Clone, PartialEq, Hash)]
-pub struct Board {
+pub struct Board<T = Grid> {
     stones: Vec<Stone>,
-    size: (usize, usize),
+    topology: T,
 
     hashes: Vec<u64>,
+    changes: Vec<Change>,
 }
 impl Board {
     /// Return a [Board] filled with Stone::Empty with the given dimensions
     pub fn empty(width: usize, height: usize) -> Self {
+        Self::with_topology(Grid { width, height })
+    }
+}
+impl<T: Topology> Board<T> {
+    /// Return an empty [Board] with the given [Topology].
+    pub fn with_topology(topology: T) -> Self {
+        let (w, h) = topology.size();
+
         Self {
-            stones: vec![Stone::Empty; width * height],
-            size: (width, height),
+            stones: vec![Stone::Empty; w * h],
+            topology,
 
             hashes: Vec::new(),
+            changes: Vec::new(),
         }
     }
 
+    pub fn topology(&self) -> &T {
+        &self.topology
+    }
+
     /// Get the index on [self.stones] corresponding to the given coordinates.
     /// Note that `x` and `y` are zero-indexed, starting from the top-left.
+    /// Points the [Topology] doesn't contain are out of bounds.
     fn index(&self, x: usize, y: usize) -> Result<usize> {
-        if x >= self.size.0 {
-            return Err(Error::CoordinatesOutOfBounds);
-        }
-        if y >= self.size.1 {
+        if !self.topology.contains((x, y)) {
             return Err(Error::CoordinatesOutOfBounds);
         }
 
-        return Ok(y * self.size.0 + x);
+        return Ok(y * self.topology.size().0 + x);
     }
 
     /// Get the [Stone] at the given coordinate
@@ -76,48 +95,45 @@ impl Board {
     pub fn place(&mut self, x: usize, y: usize, s: Stone, rules: &Rules) -> Result<PlayResponse> {
         let mut new = self.clone();
         let mut response = PlayResponse::default();
+        let mut change = Change::new(Some((x, y)), self.get(x, y)?);
+        change.setup = true;
 
         new.set(x, y, s)?;
 
-        let group = new.get_group(x, y)?;
-
-        let mut enemy_groups: Vec<Group> = Vec::new();
-        let mut categorized: HashSet<(usize, usize)> = HashSet::new();
+        new.resolve_captures(x, y, rules, &mut change, &mut response)?;
 
-        for s in group.enemy_neighbors {
-            if !categorized.contains(&s) {
-                enemy_groups.push(new.get_group(s.0, s.1)?);
-            }
+        let hash = new.position_hash();
 
-            categorized.insert(s);
-        }
+        new.hashes.push(hash);
+        new.changes.push(change);
 
-        for g in enemy_groups {
-            if g.liberties.is_empty() {
-                new.kill_group(&g)?;
+        *self = new;
 
-                if g.color == Stone::Black {
-                    response.black_captures += g.points.len() as u16;
-                }
-                if g.color == Stone::White {
-                    response.white_captures += g.points.len() as u16;
-                }
-            }
-        }
+        Ok(response)
+    }
 
-        let group = new.get_group(x, y)?;
+    /// Set each point to its [Stone] the way sgf setup properties (AB[], AW[] and AE[]) do:
+    /// nothing is captured, even a group left without liberties, so the order doesn't matter.
+    /// The whole setup is a single change for [Board::undo], and nothing is set if any point
+    /// is out of bounds.
+    pub fn setup(&mut self, stones: &[((usize, usize), Stone)]) -> Result<()> {
+        let mut change = Change::new(None, Stone::Empty);
+        change.setup = true;
 
-        if !rules.suicide_allowed && group.liberties.is_empty() {
-            return Err(Error::IllegalMove(IllegalMove::SuicidalMove));
+        for &((x, y), _) in stones {
+            change.captured.push(((x, y), self.get(x, y)?));
         }
 
-        let hash = fxhash::hash64(&new.stones);
+        for &((x, y), s) in stones {
+            self.set(x, y, s)?;
+        }
 
-        new.hashes.push(hash);
+        let hash = self.position_hash();
 
-        *self = new;
+        self.hashes.push(hash);
+        self.changes.push(change);
 
-        Ok(response)
+        Ok(())
     }
 
     /// Play a move according to the given [Rules].
@@ -130,67 +146,228 @@ impl Board {
             return Err(Error::IllegalMove(IllegalMove::NonEmptySpace));
         }
 
+        let mut change = Change::new(Some((x, y)), Stone::Empty);
+
         new.set(x, y, s)?;
 
-        let group = new.get_group(x, y)?;
+        new.resolve_captures(x, y, rules, &mut change, &mut response)?;
 
-        let mut enemy_groups: Vec<Group> = Vec::new();
-        let mut categorized: HashSet<(usize, usize)> = HashSet::new();
+        let hash = new.position_hash();
 
-        for s in group.enemy_neighbors {
-            if !categorized.contains(&s) {
-                enemy_groups.push(new.get_group(s.0, s.1)?);
-            }
+        if Some(&hash) == new.hashes.iter().rev().nth(1) {
+            return Err(Error::IllegalMove(IllegalMove::Ko));
+        }
 
-            categorized.insert(s);
+        if new.hashes.contains(&hash) && rules.superko {
+            return Err(Error::IllegalMove(IllegalMove::SuperKo));
         }
 
-        for g in enemy_groups {
-            if g.liberties.is_empty() {
-                new.kill_group(&g)?;
+        new.hashes.push(hash);
+        new.changes.push(change);
+
+        *self = new;
+
+        Ok(response)
+    }
+
+    /// Pass instead of playing a stone.
+    ///
+    /// Nothing changes on the board, but the pass is part of the [history](Board::history),
+    /// so that a ko can be retaken after a pass under the simple ko rule.
+    pub fn pass(&mut self) {
+        let hash = self.position_hash();
+
+        self.hashes.push(hash);
+        self.changes.push(Change::new(None, Stone::Empty));
+    }
+
+    /// Revert the last [Board::play], [Board::pass], [Board::place] or [Board::setup],
+    /// restoring any stones it captured.
+    pub fn undo(&mut self) -> Result<()> {
+        let change = self.changes.pop().ok_or(Error::NothingToUndo)?;
+
+        // A setup keeps the earlier stones of its points in `captured`, so they are restored
+        // in reverse in case a point was set twice.
+        for &(p, s) in change.captured.iter().rev() {
+            self.set(p.0, p.1, s)?;
+        }
+
+        if let Some(p) = change.point {
+            self.set(p.0, p.1, change.previous)?;
+        }
+
+        self.hashes.pop();
+
+        Ok(())
+    }
+
+    /// The moves made with [Board::play] and [Board::pass] so far, oldest first,
+    /// with [None] for passes. Stones added or removed with [Board::place] or [Board::setup]
+    /// are not moves.
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = Option<(usize, usize)>> + '_ {
+        self.changes.iter().filter(|c| !c.setup).map(|c| c.point)
+    }
+
+    /// The point where `turn` can't play right now because of the ko rule, if there is one.
+    pub fn ko_point(&self, turn: Stone) -> Option<(usize, usize)> {
+        let last = self.changes.last()?;
+
+        // Only a move that captured a single stone can start a ko.
+        let p = match last.captured.as_slice() {
+            &[(p, _)] if !last.setup => p,
+            _ => return None,
+        };
+
+        let rules = Rules {
+            suicide_allowed: false,
+            superko: false,
+        };
+
+        match self.clone().play(p.0, p.1, turn, &rules) {
+            Err(Error::IllegalMove(IllegalMove::Ko)) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Returns the (width, height) of the board
+    pub fn size(&self) -> (usize, usize) {
+        self.topology.size()
+    }
+
+    /// A hash of the stones on the board, ignoring its history.
+    /// This is the hash used for the ko rules.
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = FxHasher::default();
+        self.stones.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Count the area of each color as in Tromp-Taylor scoring:
+    /// every stone of that color, plus every empty point that only reaches stones of that color.
+    /// Returns (black, white).
+    pub fn area_score(&self) -> (usize, usize) {
+        let (w, h) = self.size();
 
-                if g.color == Stone::Black {
-                    response.black_captures += g.points.len() as u16;
+        let mut black = 0;
+        let mut white = 0;
+        let mut counted: HashSet<(usize, usize)> = HashSet::default();
+
+        for y in 0..h {
+            for x in 0..w {
+                if !self.topology.contains((x, y)) {
+                    continue;
                 }
-                if g.color == Stone::White {
-                    response.white_captures += g.points.len() as u16;
+
+                match self.stones[y * w + x] {
+                    Stone::Black => black += 1,
+                    Stone::White => white += 1,
+
+                    Stone::Empty => {
+                        if counted.contains(&(x, y)) {
+                            continue;
+                        }
+
+                        // For an empty point, `enemy_neighbors` holds every stone bordering the region.
+                        let region = self.get_group(x, y).unwrap();
+
+                        let reaches_black = region
+                            .enemy_neighbors
+                            .iter()
+                            .any(|p| self.stones[p.1 * w + p.0] == Stone::Black);
+                        let reaches_white = region
+                            .enemy_neighbors
+                            .iter()
+                            .any(|p| self.stones[p.1 * w + p.0] == Stone::White);
+
+                        if reaches_black && !reaches_white {
+                            black += region.points.len();
+                        }
+                        if reaches_white && !reaches_black {
+                            white += region.points.len();
+                        }
+
+                        counted.extend(region.points);
+                    }
                 }
             }
         }
 
-        let group = new.get_group(x, y)?;
+        return (black, white);
+    }
 
-        if !rules.suicide_allowed && group.liberties.is_empty() {
-            return Err(Error::IllegalMove(IllegalMove::SuicidalMove));
-        }
+    /// Remove the enemy groups left without liberties by the stone at (x, y),
+    /// then the stone's own group if it has no liberties left and [Rules::suicide_allowed] is true.
+    fn resolve_captures(
+        &mut self,
+        x: usize,
+        y: usize,
+        rules: &Rules,
+        change: &mut Change,
+        response: &mut PlayResponse,
+    ) -> Result<()> {
+        let group = self.get_group(x, y)?;
+
+        let mut enemy_groups: Vec<Group> = Vec::new();
+        let mut categorized: HashSet<(usize, usize)> = HashSet::default();
 
-        let hash = fxhash::hash64(&new.stones);
+        for s in group.enemy_neighbors {
+            if !categorized.contains(&s) {
+                let g = self.get_group(s.0, s.1)?;
 
-        if Some(&hash) == new.hashes.iter().rev().nth(1) {
-            return Err(Error::IllegalMove(IllegalMove::Ko));
+                // Mark the whole group, not just this neighbor, so that a group touching
+                // the stone at several points is only captured and counted once.
+                categorized.extend(g.points.iter().copied());
+                enemy_groups.push(g);
+            }
         }
 
-        if new.hashes.contains(&hash) && rules.superko {
-            return Err(Error::IllegalMove(IllegalMove::SuperKo));
+        for g in enemy_groups {
+            if g.liberties.is_empty() {
+                self.kill_group(&g, change, response)?;
+            }
         }
 
-        new.hashes.push(hash);
+        let group = self.get_group(x, y)?;
 
-        *self = new;
+        // Removing a stone can never be suicide.
+        if group.color != Stone::Empty && group.liberties.is_empty() {
+            if !rules.suicide_allowed {
+                return Err(Error::IllegalMove(IllegalMove::SuicidalMove));
+            }
 
-        Ok(response)
-    }
+            // The suicidal group is taken off the board, and its stones count as captured
+            // by the opponent, as under Tromp-Taylor and New Zealand rules.
+            self.kill_group(&group, change, response)?;
+        }
 
-    /// Returns the (width, height) of the board
-    pub fn size(&self) -> (usize, usize) {
-        self.size
+        // Keep equal boards equal regardless of the iteration order of the groups.
+        change.captured.sort_by_key(|c| c.0);
+
+        Ok(())
     }
 
-    fn kill_group(&mut self, g: &Group) -> Result<()> {
+    fn kill_group(
+        &mut self,
+        g: &Group,
+        change: &mut Change,
+        response: &mut PlayResponse,
+    ) -> Result<()> {
         for s in &g.points {
             self.set(s.0, s.1, Stone::Empty)?;
         }
 
+        if g.color == Stone::Black {
+            response.black_captures += g.points.len() as u16;
+        }
+        if g.color == Stone::White {
+            response.white_captures += g.points.len() as u16;
+        }
+
+        change
+            .captured
+            .extend(g.points.iter().map(|&p| (p, g.color)));
+
         Ok(())
     }
 
@@ -198,9 +375,9 @@ impl Board {
     pub fn get_group(&self, x: usize, y: usize) -> Result<Group> {
         let mut group = Group {
             color: self.get(x, y)?,
-            points: HashSet::new(),
-            liberties: HashSet::new(),
-            enemy_neighbors: HashSet::new(),
+            points: HashSet::default(),
+            liberties: HashSet::default(),
+            enemy_neighbors: HashSet::default(),
         };
 
         group.points.insert((x, y));
@@ -232,26 +409,16 @@ impl Board {
 
     /// Assumes p is in group.points
     fn build_group(&self, group: &mut Group, p: (usize, usize)) {
-        let left_edge = p.0 == 0;
-        let top_edge = p.1 == 0;
-
-        if !left_edge {
-            self.try_group_point(p.0 - 1, p.1, group);
+        for n in self.topology.neighbors(p) {
+            self.try_group_point(n.0, n.1, group);
         }
-
-        if !top_edge {
-            self.try_group_point(p.0, p.1 - 1, group);
-        }
-
-        self.try_group_point(p.0 + 1, p.1, group);
-
-        self.try_group_point(p.0, p.1 + 1, group);
     }
-
+}
+impl Board {
     pub fn star_points(&self) -> Vec<(usize, usize)> {
         let mut points = Vec::new();
 
-        let (w, h) = self.size;
+        let (w, h) = self.size();
 
         // if the board has an exact center
         if w % 2 == 1 && h % 2 == 1 {
@@ -303,6 +470,31 @@ impl Default for Board {
     }
 }
 
+/// The changes made to a [Board] by a single [Board::play], [Board::pass], [Board::place]
+/// or [Board::setup], kept so that they can be reverted by [Board::undo].
+#[derive(Clone, PartialEq, Hash)]
+struct Change {
+    /// [None] for a pass.
+    point: Option<(usize, usize)>,
+    /// The [Stone] that was at `point` before the move.
+    previous: Stone,
+    /// Points emptied by the move and the color that was on them,
+    /// or for [Board::setup], every point it set and what was there before.
+    captured: Vec<((usize, usize), Stone)>,
+    /// Whether the change was made by [Board::place] or [Board::setup] rather than being a move.
+    setup: bool,
+}
+impl Change {
+    fn new(point: Option<(usize, usize)>, previous: Stone) -> Self {
+        Self {
+            point,
+            previous,
+            captured: Vec::new(),
+            setup: false,
+        }
+    }
+}
+
 /// A set of connected stones of the same color
 pub struct Group {
     pub color: Stone,
@@ -393,6 +585,19 @@ mod board_tests {
             Err(Error::IllegalMove(IllegalMove::NonEmptySpace))
         );
     }
+
+    #[test]
+    fn place_empty() -> Result<()> {
+        let mut board = Board::empty(9, 9);
+        let rules = Rules::JAPANESE;
+
+        board.play(4, 4, Stone::Black, &rules)?;
+        board.place(4, 4, Stone::Empty, &rules)?;
+
+        assert_eq!(board.get(4, 4)?, Stone::Empty);
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -695,6 +900,95 @@ mod capturing_tests {
         Ok(())
     }
 
+    #[test]
+    fn capture_group_touching_twice() -> Result<()> {
+        let mut board = Board::empty(5, 5);
+
+        // + b b + +
+        // b w w b +
+        // b w * + +
+        // + b + + +
+        // + + + + +
+
+        let rules = Rules::JAPANESE;
+
+        board.play(1, 1, Stone::White, &rules)?;
+        board.play(2, 1, Stone::White, &rules)?;
+        board.play(1, 2, Stone::White, &rules)?;
+
+        board.play(1, 0, Stone::Black, &rules)?;
+        board.play(2, 0, Stone::Black, &rules)?;
+        board.play(0, 1, Stone::Black, &rules)?;
+        board.play(3, 1, Stone::Black, &rules)?;
+        board.play(0, 2, Stone::Black, &rules)?;
+        board.play(1, 3, Stone::Black, &rules)?;
+
+        // Black at * touches the white group at (2, 1) and at (1, 2).
+        let response = board.play(2, 2, Stone::Black, &rules)?;
+
+        assert_eq!(
+            response,
+            PlayResponse {
+                black_captures: 0,
+                white_captures: 3,
+            }
+        );
+        assert_eq!(board.get(1, 1)?, Stone::Empty);
+
+        board.undo()?;
+        assert_eq!(board.get(1, 1)?, Stone::White);
+        assert_eq!(board.get(2, 2)?, Stone::Empty);
+
+        Ok(())
+    }
+
+    #[test]
+    fn suicide() -> Result<()> {
+        let mut board = Board::empty(5, 5);
+
+        // * b w + +
+        // w w + + +
+        // + + + + +
+        // + + + + +
+        // + + + + +
+
+        let rules = Rules {
+            suicide_allowed: true,
+            superko: true,
+        };
+
+        board.play(1, 0, Stone::Black, &rules)?;
+        board.play(2, 0, Stone::White, &rules)?;
+        board.play(0, 1, Stone::White, &rules)?;
+        board.play(1, 1, Stone::White, &rules)?;
+
+        let before = board.clone();
+
+        assert_eq!(
+            board.play(0, 0, Stone::Black, &Rules::JAPANESE),
+            Err(Error::IllegalMove(IllegalMove::SuicidalMove))
+        );
+        assert!(board == before);
+
+        // Black at * takes the last liberty of its own two stones, which are removed.
+        let response = board.play(0, 0, Stone::Black, &rules)?;
+
+        assert_eq!(
+            response,
+            PlayResponse {
+                black_captures: 2,
+                white_captures: 0,
+            }
+        );
+        assert_eq!(board.get(0, 0)?, Stone::Empty);
+        assert_eq!(board.get(1, 0)?, Stone::Empty);
+
+        board.undo()?;
+        assert!(board == before);
+
+        Ok(())
+    }
+
     #[test]
     fn single_ko() -> Result<()> {
         let mut board = Board::empty(9, 9);
@@ -731,3 +1025,295 @@ mod capturing_tests {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    #[test]
+    fn moves_and_passes() -> Result<()> {
+        let mut board = Board::empty(9, 9);
+        let rules = Rules::JAPANESE;
+
+        board.place(2, 2, Stone::Black, &rules)?;
+        board.play(4, 4, Stone::White, &rules)?;
+        board.pass();
+        board.play(3, 3, Stone::White, &rules)?;
+
+        let history: Vec<_> = board.history().collect();
+        assert_eq!(history, [Some((4, 4)), None, Some((3, 3))]);
+
+        board.undo()?;
+        board.undo()?;
+        assert_eq!(board.history().last(), Some(Some((4, 4))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ko_after_passes() -> Result<()> {
+        let mut board = Board::empty(4, 3);
+        let rules = Rules {
+            suicide_allowed: false,
+            superko: false,
+        };
+
+        // + b w +
+        // b w * w
+        // + b w +
+        for (x, y) in [(1, 0), (0, 1), (1, 2)] {
+            board.play(x, y, Stone::Black, &rules)?;
+        }
+        for (x, y) in [(2, 0), (1, 1), (3, 1), (2, 2)] {
+            board.play(x, y, Stone::White, &rules)?;
+        }
+
+        // Black takes the ko at *.
+        board.play(2, 1, Stone::Black, &rules)?;
+        assert_eq!(board.ko_point(Stone::White), Some((1, 1)));
+
+        // White passes, so black has no ko to worry about.
+        board.pass();
+        assert_eq!(board.ko_point(Stone::Black), None);
+
+        // After black passes too, white may take the ko back.
+        board.pass();
+        assert_eq!(board.ko_point(Stone::White), None);
+        board.play(1, 1, Stone::White, &rules)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod undo_tests {
+    use super::*;
+
+    #[test]
+    fn undo_restores_captures() -> Result<()> {
+        let mut board = Board::empty(9, 9);
+
+        // + b + + + + + + +
+        // b w b + + + + + +
+        // + + + + + + + + +
+        // + + + + + + + + +
+        // + + + + + + + + +
+        // + + + + + + + + +
+        // + + + + + + + + +
+        // + + + + + + + + +
+        // + + + + + + + + +
+
+        let rules = Rules::JAPANESE;
+
+        board.play(1, 1, Stone::White, &rules)?;
+        board.play(1, 0, Stone::Black, &rules)?;
+        board.play(0, 1, Stone::Black, &rules)?;
+        board.play(2, 1, Stone::Black, &rules)?;
+
+        let before = board.clone();
+
+        board.play(1, 2, Stone::Black, &rules)?;
+        assert_eq!(board.get(1, 1)?, Stone::Empty);
+
+        board.undo()?;
+
+        assert!(board == before);
+        assert_eq!(board.get(1, 1)?, Stone::White);
+        assert_eq!(board.get(1, 2)?, Stone::Empty);
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_place() -> Result<()> {
+        let mut board = Board::empty(9, 9);
+        let rules = Rules::JAPANESE;
+
+        board.play(4, 4, Stone::Black, &rules)?;
+        board.place(4, 4, Stone::White, &rules)?;
+
+        board.undo()?;
+        assert_eq!(board.get(4, 4)?, Stone::Black);
+
+        board.undo()?;
+        assert_eq!(board.get(4, 4)?, Stone::Empty);
+
+        assert_eq!(board.undo(), Err(Error::NothingToUndo));
+
+        Ok(())
+    }
+
+    #[test]
+    fn setup_never_captures() -> Result<()> {
+        let mut board = Board::empty(3, 3);
+
+        // + b +
+        // b w b
+        // + b +
+        board.setup(&[
+            ((1, 1), Stone::White),
+            ((1, 0), Stone::Black),
+            ((0, 1), Stone::Black),
+            ((2, 1), Stone::Black),
+            ((1, 2), Stone::Black),
+        ])?;
+
+        assert_eq!(board.get(1, 1)?, Stone::White);
+        assert_eq!(board.get(1, 0)?, Stone::Black);
+        assert_eq!(board.history().count(), 0);
+        assert_eq!(board.ko_point(Stone::White), None);
+
+        board.undo()?;
+        assert!(board == Board::empty(3, 3));
+
+        assert_eq!(
+            board.setup(&[((0, 0), Stone::Black), ((3, 0), Stone::Black)]),
+            Err(Error::CoordinatesOutOfBounds)
+        );
+        assert_eq!(board.get(0, 0)?, Stone::Empty);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod score_tests {
+    use super::*;
+
+    #[test]
+    fn empty_board_score() {
+        assert_eq!(Board::empty(9, 9).area_score(), (0, 0));
+    }
+
+    #[test]
+    fn split_board_score() -> Result<()> {
+        let mut board = Board::empty(5, 5);
+
+        // + b w + +
+        // + b w + +
+        // + b w + +
+        // + b w + +
+        // + b w + +
+
+        let rules = Rules::JAPANESE;
+
+        for y in 0..5 {
+            board.play(1, y, Stone::Black, &rules)?;
+            board.play(2, y, Stone::White, &rules)?;
+        }
+
+        assert_eq!(board.area_score(), (10, 15));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shared_region_score() -> Result<()> {
+        let mut board = Board::empty(5, 5);
+        let rules = Rules::JAPANESE;
+
+        board.play(0, 0, Stone::Black, &rules)?;
+        board.play(4, 4, Stone::White, &rules)?;
+
+        assert_eq!(board.area_score(), (1, 1));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod proptest_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// A board size and a sequence of (x, y, color) attempts on it, some of which may be illegal.
+    fn game() -> impl Strategy<Value = (usize, Vec<(usize, usize, bool)>)> {
+        (2usize..=7).prop_flat_map(|size| {
+            let moves = prop::collection::vec((0..size, 0..size, any::<bool>()), 0..80);
+
+            (Just(size), moves)
+        })
+    }
+
+    fn count(board: &Board, s: Stone) -> usize {
+        board.stones.iter().filter(|&&t| t == s).count()
+    }
+
+    fn no_dead_groups(board: &Board) -> bool {
+        let (w, h) = board.size();
+
+        (0..h).all(|y| {
+            (0..w).all(|x| {
+                let g = board.get_group(x, y).unwrap();
+
+                g.color == Stone::Empty || !g.liberties.is_empty()
+            })
+        })
+    }
+
+    proptest! {
+        // Checking every group after every move is slow in debug builds.
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn invariants((size, moves) in game(), suicide_allowed: bool, superko: bool) {
+            let rules = Rules { suicide_allowed, superko };
+            let mut board = Board::empty(size, size);
+
+            for (x, y, black) in moves {
+                let s = if black { Stone::Black } else { Stone::White };
+                let before = board.clone();
+
+                match board.play(x, y, s, &rules) {
+                    Ok(response) => {
+                        let removed_black = count(&before, Stone::Black) + usize::from(s == Stone::Black) - count(&board, Stone::Black);
+                        let removed_white = count(&before, Stone::White) + usize::from(s == Stone::White) - count(&board, Stone::White);
+
+                        prop_assert_eq!(response.black_captures as usize, removed_black);
+                        prop_assert_eq!(response.white_captures as usize, removed_white);
+
+                        prop_assert!(no_dead_groups(&board));
+
+                        prop_assert_eq!(board.hashes.len(), board.changes.len());
+                        prop_assert_eq!(board.hashes.last(), Some(&board.position_hash()));
+
+                        let mut undone = board.clone();
+                        undone.undo().unwrap();
+                        prop_assert!(undone == before);
+                    }
+                    Err(_) => prop_assert!(board == before),
+                }
+            }
+
+            // Undoing everything should walk back through every recorded hash.
+            while let Some(&hash) = board.hashes.last() {
+                prop_assert_eq!(hash, board.position_hash());
+                board.undo().unwrap();
+            }
+
+            prop_assert!(board == Board::empty(size, size));
+        }
+
+        #[test]
+        fn place_then_undo((size, moves) in game(), suicide_allowed: bool) {
+            let rules = Rules { suicide_allowed, superko: false };
+            let mut board = Board::empty(size, size);
+
+            for (x, y, black) in moves {
+                let s = if black { Stone::Black } else { Stone::Empty };
+                let before = board.clone();
+
+                match board.place(x, y, s, &rules) {
+                    Ok(_) => {
+                        prop_assert!(no_dead_groups(&board));
+
+                        let mut undone = board.clone();
+                        undone.undo().unwrap();
+                        prop_assert!(undone == before);
+                    }
+                    Err(_) => prop_assert!(board == before),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,494 @@
+use alloc::rc::Rc;
+use core::cell::Cell;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::Stone;
+
+/// Where a [Clock] gets the current time from.
+pub trait TimeSource {
+    /// The time elapsed since some fixed starting point. Must never go backwards.
+    fn now(&self) -> Duration;
+}
+
+/// The real, monotonic time of the system.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+pub struct MonotonicTime {
+    start: Instant,
+}
+#[cfg(feature = "std")]
+impl MonotonicTime {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl Default for MonotonicTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(feature = "std")]
+impl TimeSource for MonotonicTime {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A time source that only moves when told to, for tests.
+/// Clones share the same time, so a clone can be kept to control a [Clock].
+#[derive(Clone, Default, Debug)]
+pub struct ManualTime {
+    now: Rc<Cell<Duration>>,
+}
+impl ManualTime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, d: Duration) {
+        self.now.set(self.now.get() + d);
+    }
+}
+impl TimeSource for ManualTime {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// The time each player gets. Every control starts with `main` time.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TimeControl {
+    /// The player loses when the main time runs out.
+    Absolute { main: Duration },
+    /// Japanese byo-yomi: after the main time, each move has to be made within `period`.
+    /// Going over a period uses it up, and the player loses when all `periods` are used.
+    ByoYomi {
+        main: Duration,
+        period: Duration,
+        periods: u32,
+    },
+    /// Canadian overtime: after the main time, `stones` moves have to be made within each `period`.
+    /// With no `stones` there is no overtime, as with [TimeControl::Absolute].
+    Canadian {
+        main: Duration,
+        period: Duration,
+        stones: u32,
+    },
+    /// `increment` is added after each move, but the time left never goes above `max`.
+    Fischer {
+        main: Duration,
+        increment: Duration,
+        max: Option<Duration>,
+    },
+    /// The first `delay` of each move is free.
+    Delay { main: Duration, delay: Duration },
+}
+impl TimeControl {
+    pub fn main(&self) -> Duration {
+        match *self {
+            Self::Absolute { main }
+            | Self::ByoYomi { main, .. }
+            | Self::Canadian { main, .. }
+            | Self::Fischer { main, .. }
+            | Self::Delay { main, .. } => main,
+        }
+    }
+}
+
+/// The time one player has left.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct PlayerTime {
+    main: Duration,
+    /// Time left in the current overtime period.
+    period: Duration,
+    /// Byo-yomi periods or Canadian stones left.
+    overtime: u32,
+    out: bool,
+}
+impl PlayerTime {
+    fn new(control: &TimeControl) -> Self {
+        let (period, overtime) = match *control {
+            TimeControl::ByoYomi {
+                period, periods, ..
+            } => (period, periods),
+            TimeControl::Canadian { period, stones, .. } if stones > 0 => (period, stones),
+            _ => (Duration::ZERO, 0),
+        };
+
+        Self {
+            main: control.main(),
+            period,
+            overtime,
+            out: false,
+        }
+    }
+
+    /// Take the time used by a move that took `elapsed`.
+    /// `finished` is false if the move is still being thought about.
+    fn charge(&mut self, control: &TimeControl, elapsed: Duration, finished: bool) {
+        if self.out {
+            return;
+        }
+
+        let elapsed = match *control {
+            TimeControl::Delay { delay, .. } => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        };
+
+        let over = elapsed.saturating_sub(self.main);
+        self.main = self.main.saturating_sub(elapsed);
+
+        match *control {
+            TimeControl::Absolute { .. }
+            | TimeControl::Delay { .. }
+            | TimeControl::Canadian { stones: 0, .. } => {
+                if over > Duration::ZERO || self.main == Duration::ZERO {
+                    self.out = true;
+                }
+            }
+
+            TimeControl::ByoYomi { period, .. } => {
+                if over > Duration::ZERO {
+                    // Periods that ran out.
+                    let used = (over.as_nanos() / period.as_nanos().max(1)) as u32;
+
+                    if used >= self.overtime {
+                        self.overtime = 0;
+                        self.period = Duration::ZERO;
+                        self.out = true;
+                        return;
+                    }
+
+                    self.overtime -= used;
+                    self.period = period * (used + 1) - over;
+                }
+
+                if finished {
+                    self.period = period;
+                }
+            }
+
+            TimeControl::Canadian { period, stones, .. } => {
+                if over > self.period || (over == self.period && over > Duration::ZERO) {
+                    self.period = Duration::ZERO;
+                    self.out = true;
+                    return;
+                }
+
+                // Moves made in the main time don't count towards the overtime stones.
+                let in_overtime = over > Duration::ZERO || self.main == Duration::ZERO;
+
+                self.period -= over;
+
+                if finished && in_overtime {
+                    self.overtime -= 1;
+
+                    if self.overtime == 0 {
+                        self.overtime = stones;
+                        self.period = period;
+                    }
+                }
+            }
+
+            TimeControl::Fischer { increment, max, .. } => {
+                if over > Duration::ZERO || self.main == Duration::ZERO {
+                    self.out = true;
+                    return;
+                }
+
+                if finished {
+                    self.main += increment;
+
+                    if let Some(max) = max {
+                        self.main = self.main.min(max);
+                    }
+                }
+            }
+        }
+    }
+
+    fn time_left(&self) -> Duration {
+        if self.main > Duration::ZERO {
+            return self.main;
+        }
+
+        self.period
+    }
+}
+
+/// The [TimeSource] of a [Clock] by default. Without std there is no system time to read.
+#[cfg(feature = "std")]
+type DefaultSource = MonotonicTime;
+#[cfg(not(feature = "std"))]
+type DefaultSource = ManualTime;
+
+/// A game clock for two players.
+///
+/// Only one player's time runs at once. [Clock::press] ends that player's move and starts the other's.
+pub struct Clock<T: TimeSource = DefaultSource> {
+    control: TimeControl,
+    source: T,
+
+    black: PlayerTime,
+    white: PlayerTime,
+    /// The player whose time is running, and when their move started.
+    running: Option<(Stone, Duration)>,
+}
+impl<T: TimeSource> Clock<T> {
+    pub fn new(control: TimeControl, source: T) -> Self {
+        Self {
+            black: PlayerTime::new(&control),
+            white: PlayerTime::new(&control),
+            control,
+            source,
+
+            running: None,
+        }
+    }
+
+    pub fn control(&self) -> &TimeControl {
+        &self.control
+    }
+
+    /// The player whose time is running.
+    pub fn running(&self) -> Option<Stone> {
+        self.running.map(|r| r.0)
+    }
+
+    /// Start the time of `color`, ending the move of the player whose time was running.
+    pub fn start(&mut self, color: Stone) {
+        self.stop();
+
+        if color != Stone::Empty {
+            self.running = Some((color, self.source.now()));
+        }
+    }
+
+    /// Stop the clock, ending the move of the player whose time was running.
+    pub fn stop(&mut self) {
+        if let Some((color, start)) = self.running.take() {
+            let elapsed = self.source.now().saturating_sub(start);
+            let control = self.control;
+
+            if let Some(p) = self.player_mut(color) {
+                p.charge(&control, elapsed, true);
+            }
+        }
+    }
+
+    /// End the running player's move and start the other player's time.
+    /// Returns the player who ran out of time, if either has.
+    pub fn press(&mut self) -> Option<Stone> {
+        if let Some(color) = self.running() {
+            self.start(!color);
+        }
+
+        self.flagged()
+    }
+
+    /// The player who has run out of time, if either has.
+    pub fn flagged(&self) -> Option<Stone> {
+        [Stone::Black, Stone::White]
+            .into_iter()
+            .find(|&c| self.current(c).is_some_and(|p| p.out))
+    }
+
+    /// The main time left for `color`, or the time left in the current overtime period
+    /// once the main time has run out.
+    pub fn time_left(&self, color: Stone) -> Duration {
+        self.current(color)
+            .map(|p| p.time_left())
+            .unwrap_or_default()
+    }
+
+    /// The byo-yomi periods or Canadian overtime stones left for `color`,
+    /// once their main time has run out.
+    pub fn overtime_left(&self, color: Stone) -> Option<u32> {
+        let p = self.current(color)?;
+
+        match self.control {
+            TimeControl::Canadian { stones: 0, .. } => None,
+
+            TimeControl::ByoYomi { .. } | TimeControl::Canadian { .. }
+                if p.main == Duration::ZERO =>
+            {
+                Some(p.overtime)
+            }
+
+            _ => None,
+        }
+    }
+
+    /// The time of `color` as it is now, including the move in progress.
+    fn current(&self, color: Stone) -> Option<PlayerTime> {
+        let mut p = match color {
+            Stone::Black => self.black,
+            Stone::White => self.white,
+            Stone::Empty => return None,
+        };
+
+        if let Some((running, start)) = self.running {
+            if running == color {
+                p.charge(
+                    &self.control,
+                    self.source.now().saturating_sub(start),
+                    false,
+                );
+            }
+        }
+
+        Some(p)
+    }
+
+    fn player_mut(&mut self, color: Stone) -> Option<&mut PlayerTime> {
+        match color {
+            Stone::Black => Some(&mut self.black),
+            Stone::White => Some(&mut self.white),
+            Stone::Empty => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    fn clock(control: TimeControl) -> (Clock<ManualTime>, ManualTime) {
+        let time = ManualTime::new();
+        let mut clock = Clock::new(control, time.clone());
+
+        clock.start(Stone::Black);
+
+        (clock, time)
+    }
+
+    #[test]
+    fn absolute() {
+        let (mut clock, time) = clock(TimeControl::Absolute { main: secs(60) });
+
+        time.advance(secs(20));
+        assert_eq!(clock.press(), None);
+
+        assert_eq!(clock.time_left(Stone::Black), secs(40));
+        assert_eq!(clock.running(), Some(Stone::White));
+
+        time.advance(secs(59));
+        assert_eq!(clock.time_left(Stone::White), secs(1));
+        assert_eq!(clock.flagged(), None);
+
+        time.advance(secs(1));
+        assert_eq!(clock.flagged(), Some(Stone::White));
+        assert_eq!(clock.overtime_left(Stone::White), None);
+    }
+
+    #[test]
+    fn byo_yomi() {
+        let (mut clock, time) = clock(TimeControl::ByoYomi {
+            main: secs(10),
+            period: secs(30),
+            periods: 3,
+        });
+
+        // Main time runs out, 25 seconds into the first period.
+        time.advance(secs(35));
+        assert_eq!(clock.time_left(Stone::Black), secs(5));
+        assert_eq!(clock.overtime_left(Stone::Black), Some(3));
+
+        // Made it in time, so the period is reset.
+        clock.press();
+        assert_eq!(clock.time_left(Stone::Black), secs(30));
+        assert_eq!(clock.overtime_left(Stone::Black), Some(3));
+
+        // 70 seconds uses up two periods.
+        clock.start(Stone::Black);
+        time.advance(secs(70));
+        clock.press();
+        assert_eq!(clock.overtime_left(Stone::Black), Some(1));
+
+        clock.start(Stone::Black);
+        time.advance(secs(30));
+        assert_eq!(clock.flagged(), Some(Stone::Black));
+    }
+
+    #[test]
+    fn canadian() {
+        let (mut clock, time) = clock(TimeControl::Canadian {
+            main: secs(10),
+            period: secs(60),
+            stones: 2,
+        });
+
+        time.advance(secs(30));
+        clock.press();
+        assert_eq!(clock.time_left(Stone::Black), secs(40));
+        assert_eq!(clock.overtime_left(Stone::Black), Some(1));
+
+        // The second stone finishes the period, so a new one starts.
+        clock.start(Stone::Black);
+        time.advance(secs(20));
+        clock.press();
+        assert_eq!(clock.time_left(Stone::Black), secs(60));
+        assert_eq!(clock.overtime_left(Stone::Black), Some(2));
+
+        clock.start(Stone::Black);
+        time.advance(secs(60));
+        assert_eq!(clock.press(), Some(Stone::Black));
+    }
+
+    #[test]
+    fn canadian_without_stones() {
+        let (mut clock, time) = clock(TimeControl::Canadian {
+            main: secs(10),
+            period: secs(60),
+            stones: 0,
+        });
+
+        // Using up exactly the main time leaves no overtime to play in.
+        time.advance(secs(10));
+        assert_eq!(clock.press(), Some(Stone::Black));
+        assert_eq!(clock.time_left(Stone::Black), Duration::ZERO);
+        assert_eq!(clock.overtime_left(Stone::Black), None);
+    }
+
+    #[test]
+    fn fischer() {
+        let (mut clock, time) = clock(TimeControl::Fischer {
+            main: secs(60),
+            increment: secs(10),
+            max: Some(secs(65)),
+        });
+
+        time.advance(secs(20));
+        clock.press();
+        assert_eq!(clock.time_left(Stone::Black), secs(50));
+
+        // White would have 70 seconds, but is capped at 65.
+        time.advance(secs(0));
+        clock.press();
+        assert_eq!(clock.time_left(Stone::White), secs(65));
+    }
+
+    #[test]
+    fn delay() {
+        let (mut clock, time) = clock(TimeControl::Delay {
+            main: secs(60),
+            delay: secs(5),
+        });
+
+        time.advance(secs(4));
+        clock.press();
+        assert_eq!(clock.time_left(Stone::Black), secs(60));
+
+        time.advance(secs(15));
+        assert_eq!(clock.time_left(Stone::White), secs(50));
+    }
+}
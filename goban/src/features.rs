@@ -0,0 +1,287 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Board, Stone, Topology};
+
+/// One input plane for a neural network: a value of 0 or 1 for every point of the board.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Plane {
+    /// Points with a stone of the given color, or empty points for [Stone::Empty].
+    Stones(Stone),
+    /// Stones of the side to move.
+    OwnStones,
+    /// Stones of the side not to move.
+    OpponentStones,
+    /// Stones in groups with exactly this many liberties.
+    Liberties(usize),
+    /// Stones in groups with at least this many liberties.
+    LibertiesAtLeast(usize),
+    /// The point the side to move can't play on because of the ko rule.
+    KoPoint,
+    /// The point played this many moves ago, 0 being the last move.
+    /// Passes count as moves, and setup stones don't.
+    LastMove(usize),
+    /// Every point if black is to move.
+    BlackToMove,
+    /// Every point, so that the network can see the edges of the board.
+    Ones,
+}
+
+/// How the planes are arranged in a buffer.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Order {
+    /// Each plane in turn, row by row: (planes, height, width).
+    #[default]
+    PlanesFirst,
+    /// Every plane of each point in turn, row by row: (height, width, planes).
+    PlanesLast,
+}
+
+/// A set of [Plane]s and how they are laid out.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Layout {
+    pub planes: Vec<Plane>,
+    pub order: Order,
+}
+impl Layout {
+    pub fn new(planes: Vec<Plane>) -> Self {
+        Self {
+            planes,
+            order: Order::default(),
+        }
+    }
+
+    /// Own and opponent stones, liberties 1, 2 and 3+, the ko point,
+    /// the last `history` moves and the side to move.
+    pub fn standard(history: usize) -> Self {
+        let mut planes = vec![
+            Plane::OwnStones,
+            Plane::OpponentStones,
+            Plane::Liberties(1),
+            Plane::Liberties(2),
+            Plane::LibertiesAtLeast(3),
+            Plane::KoPoint,
+        ];
+
+        planes.extend((0..history).map(Plane::LastMove));
+        planes.push(Plane::BlackToMove);
+
+        Self::new(planes)
+    }
+
+    /// The length of a buffer holding the planes of a board of the given (width, height).
+    pub fn values_len(&self, size: (usize, usize)) -> usize {
+        self.planes.len() * size.0 * size.1
+    }
+
+    /// Write the planes of `board` with `turn` to move into `out`.
+    /// Points outside the [Topology] are 0 in every plane.
+    ///
+    /// # Panics
+    /// If `out` is not [Layout::values_len] long.
+    pub fn write<T: Topology, V: From<u8>>(&self, board: &Board<T>, turn: Stone, out: &mut [V]) {
+        let (w, h) = board.size();
+        let points = w * h;
+
+        assert_eq!(
+            out.len(),
+            self.values_len((w, h)),
+            "wrong feature buffer length"
+        );
+
+        let liberties = liberty_counts(board);
+        let ko = board.ko_point(turn);
+        let history: Vec<Option<(usize, usize)>> = board.history().rev().collect();
+
+        for y in 0..h {
+            for x in 0..w {
+                let i = y * w + x;
+                let on_board = board.topology().contains((x, y));
+                let stone = board.get(x, y).unwrap_or(Stone::Empty);
+
+                for (p, plane) in self.planes.iter().enumerate() {
+                    let value = on_board
+                        && match *plane {
+                            Plane::Stones(s) => stone == s,
+                            Plane::OwnStones => stone != Stone::Empty && stone == turn,
+                            Plane::OpponentStones => stone != Stone::Empty && stone == !turn,
+                            Plane::Liberties(n) => stone != Stone::Empty && liberties[i] == n,
+                            Plane::LibertiesAtLeast(n) => {
+                                stone != Stone::Empty && liberties[i] >= n
+                            }
+                            Plane::KoPoint => ko == Some((x, y)),
+                            Plane::LastMove(n) => history.get(n) == Some(&Some((x, y))),
+                            Plane::BlackToMove => turn == Stone::Black,
+                            Plane::Ones => true,
+                        };
+
+                    let index = match self.order {
+                        Order::PlanesFirst => p * points + i,
+                        Order::PlanesLast => i * self.planes.len() + p,
+                    };
+
+                    out[index] = V::from(value as u8);
+                }
+            }
+        }
+    }
+
+    pub fn to_u8<T: Topology>(&self, board: &Board<T>, turn: Stone) -> Vec<u8> {
+        let mut out = vec![0; self.values_len(board.size())];
+        self.write(board, turn, &mut out);
+
+        return out;
+    }
+
+    pub fn to_f32<T: Topology>(&self, board: &Board<T>, turn: Stone) -> Vec<f32> {
+        let mut out = vec![0.0; self.values_len(board.size())];
+        self.write(board, turn, &mut out);
+
+        return out;
+    }
+}
+
+/// The number of liberties of the group on each point, row by row, or 0 for empty points.
+fn liberty_counts<T: Topology>(board: &Board<T>) -> Vec<usize> {
+    let (w, h) = board.size();
+    let mut counts = vec![0; w * h];
+    let mut done = vec![false; w * h];
+
+    for y in 0..h {
+        for x in 0..w {
+            if done[y * w + x] || !matches!(board.get(x, y), Ok(Stone::Black | Stone::White)) {
+                continue;
+            }
+
+            let group = board.get_group(x, y).unwrap();
+
+            for p in &group.points {
+                counts[p.1 * w + p.0] = group.liberties.len();
+                done[p.1 * w + p.0] = true;
+            }
+        }
+    }
+
+    return counts;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Result, Rules};
+
+    fn plane(layout: &Layout, out: &[u8], n: usize) -> Vec<u8> {
+        let len = out.len() / layout.planes.len();
+
+        out[n * len..(n + 1) * len].to_vec()
+    }
+
+    #[test]
+    fn stones_and_liberties() -> Result<()> {
+        let mut board = Board::empty(3, 3);
+        let rules = Rules::JAPANESE;
+
+        // b b +
+        // w + +
+        // + + +
+        board.play(0, 0, Stone::Black, &rules)?;
+        board.play(0, 1, Stone::White, &rules)?;
+        board.play(1, 0, Stone::Black, &rules)?;
+
+        let layout = Layout::standard(2);
+        let out = layout.to_u8(&board, Stone::White);
+
+        assert_eq!(out.len(), 9 * 9);
+
+        #[rustfmt::skip]
+        let expected: [[u8; 9]; 9] = [
+            // Own (white) stones.
+            [0, 0, 0, 1, 0, 0, 0, 0, 0],
+            // Opponent (black) stones.
+            [1, 1, 0, 0, 0, 0, 0, 0, 0],
+            // 1, 2 and 3+ liberties.
+            [0, 0, 0, 0, 0, 0, 0, 0, 0],
+            [1, 1, 0, 1, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0, 0, 0],
+            // Ko point.
+            [0; 9],
+            // Last two moves.
+            [0, 1, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 1, 0, 0, 0, 0, 0],
+            // Black to move.
+            [0; 9],
+        ];
+
+        for (n, e) in expected.iter().enumerate() {
+            assert_eq!(plane(&layout, &out, n), e, "plane {n}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn ko_point() -> Result<()> {
+        let mut board = Board::empty(4, 3);
+        let rules = Rules::JAPANESE;
+
+        // + b w +
+        // b w + w
+        // + b w +
+        for (x, y) in [(1, 0), (0, 1), (1, 2)] {
+            board.play(x, y, Stone::Black, &rules)?;
+        }
+        for (x, y) in [(2, 0), (1, 1), (3, 1), (2, 2)] {
+            board.play(x, y, Stone::White, &rules)?;
+        }
+
+        // Black takes the ko.
+        board.play(2, 1, Stone::Black, &rules)?;
+
+        let layout = Layout::new(vec![Plane::KoPoint]);
+
+        let mut expected = vec![0.0; 12];
+        expected[5] = 1.0;
+
+        assert_eq!(layout.to_f32(&board, Stone::White), expected);
+        assert_eq!(layout.to_f32(&board, Stone::Black), vec![0.0; 12]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn last_moves_skip_setup() -> Result<()> {
+        let mut board = Board::empty(3, 1);
+        let rules = Rules::JAPANESE;
+
+        // A handicap stone, a white move and a black pass.
+        board.place(0, 0, Stone::Black, &rules)?;
+        board.play(2, 0, Stone::White, &rules)?;
+        board.pass();
+
+        let layout = Layout::new(vec![
+            Plane::LastMove(0),
+            Plane::LastMove(1),
+            Plane::LastMove(2),
+        ]);
+
+        assert_eq!(
+            layout.to_u8(&board, Stone::White),
+            [0, 0, 0, 0, 0, 1, 0, 0, 0]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn planes_last() -> Result<()> {
+        let mut board = Board::empty(2, 1);
+        board.play(1, 0, Stone::Black, &Rules::JAPANESE)?;
+
+        let mut layout = Layout::new(vec![Plane::Stones(Stone::Black), Plane::Ones]);
+        layout.order = Order::PlanesLast;
+
+        assert_eq!(layout.to_u8(&board, Stone::White), [0, 1, 1, 1]);
+
+        Ok(())
+    }
+}
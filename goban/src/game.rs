@@ -0,0 +1,494 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::board::PlayResponse;
+use crate::rule::{IllegalMove, Rules};
+use crate::turn_order::TurnOrder;
+use crate::{Board, Error, Result, Stone};
+
+/// The kind of game being played on top of the usual [Rules].
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Variant {
+    /// Normal go, decided by counting once both players pass.
+    #[default]
+    Standard,
+    /// The first player to capture at least `target` stones wins.
+    /// Passing is not allowed.
+    Capture { target: u32 },
+    /// Normal go, but every stone is shown in the same color,
+    /// so the players have to remember whose stones are whose.
+    OneColor,
+}
+impl Variant {
+    /// First capture wins.
+    pub const ATARI_GO: Self = Self::Capture { target: 1 };
+
+    /// The variants offered by default, for menus.
+    pub const ALL: [Self; 4] = [
+        Self::Standard,
+        Self::ATARI_GO,
+        Self::Capture { target: 5 },
+        Self::OneColor,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Standard => String::from("Standard"),
+            Self::Capture { target: 1 } => String::from("Atari Go"),
+            Self::Capture { target } => format!("Capture Go ({target} stones)"),
+            Self::OneColor => String::from("One-color Go"),
+        }
+    }
+
+    /// Whether players may pass by default.
+    pub fn allows_pass(&self) -> bool {
+        !matches!(self, Self::Capture { .. })
+    }
+
+    /// Whether stones should be shown without their colors.
+    pub fn hides_colors(&self) -> bool {
+        *self == Self::OneColor
+    }
+}
+
+/// Why a [Game] ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameResult {
+    Win {
+        winner: Stone,
+        reason: WinReason,
+    },
+    /// Both players passed in a row. The winner has to be decided by counting.
+    Counting,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WinReason {
+    /// The winner captured enough stones for a [Variant::Capture] game.
+    Captures,
+    Resignation,
+    /// The loser ran out of time on their [Clock](crate::Clock).
+    Time,
+}
+
+/// A move made in a [Game].
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameMove {
+    pub color: Stone,
+    /// [None] for a pass.
+    pub point: Option<(usize, usize)>,
+    /// The player who made the move, if the game has a [TurnOrder].
+    pub player: Option<String>,
+}
+
+/// A game in progress: a [Board], whose turn it is, and the moves made so far,
+/// played according to a [Variant].
+#[derive(Clone)]
+pub struct Game {
+    board: Board,
+    rules: Rules,
+    variant: Variant,
+    /// Whether [Game::pass] is allowed. Defaults to [Variant::allows_pass].
+    pub pass_allowed: bool,
+
+    turn: Stone,
+    /// Stones captured by (black, white).
+    captures: (u32, u32),
+    passes: usize,
+    result: Option<GameResult>,
+
+    moves: Vec<GameMove>,
+    turn_order: Option<TurnOrder>,
+}
+impl Game {
+    /// A game starting from `board` with black to play.
+    pub fn new(board: Board, rules: Rules, variant: Variant) -> Self {
+        Self {
+            board,
+            rules,
+            variant,
+            pass_allowed: variant.allows_pass(),
+
+            turn: Stone::Black,
+            captures: (0, 0),
+            passes: 0,
+            result: None,
+
+            moves: Vec::new(),
+            turn_order: None,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// The color to play next.
+    pub fn turn(&self) -> Stone {
+        self.turn
+    }
+
+    /// Set the color to play next, such as white after black's handicap stones.
+    pub fn set_turn(&mut self, color: Stone) {
+        self.turn = color;
+    }
+
+    /// The number of stones captured by `color`.
+    pub fn captures(&self, color: Stone) -> u32 {
+        match color {
+            Stone::Black => self.captures.0,
+            Stone::White => self.captures.1,
+            Stone::Empty => 0,
+        }
+    }
+
+    /// [None] while the game is still going.
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+
+    /// Every move made so far, passes included.
+    pub fn moves(&self) -> &[GameMove] {
+        &self.moves
+    }
+
+    pub fn turn_order(&self) -> Option<&TurnOrder> {
+        self.turn_order.as_ref()
+    }
+
+    /// Set the players taking turns in a team game.
+    /// Moves already made by a color count towards whose turn it is within that color's team.
+    pub fn set_turn_order(&mut self, order: TurnOrder) {
+        self.turn_order = Some(order);
+    }
+
+    /// The player who has to make the next move, if the game has a [TurnOrder].
+    pub fn current_player(&self) -> Option<&str> {
+        let n = self.moves.iter().filter(|m| m.color == self.turn).count();
+
+        self.turn_order.as_ref()?.player(self.turn, n)
+    }
+
+    fn record(&mut self, point: Option<(usize, usize)>) {
+        let player = self.current_player().map(String::from);
+
+        self.moves.push(GameMove {
+            color: self.turn,
+            point,
+            player,
+        });
+    }
+
+    /// Play a stone for the color whose turn it is.
+    /// Note that `x` and `y` are zero-indexed, starting from the top-left.
+    pub fn play(&mut self, x: usize, y: usize) -> Result<PlayResponse> {
+        if self.result.is_some() {
+            return Err(Error::GameOver);
+        }
+
+        let response = self.board.play(x, y, self.turn, &self.rules)?;
+
+        self.captures.0 += response.white_captures as u32;
+        self.captures.1 += response.black_captures as u32;
+
+        // A suicide, when the rules allow it, counts as captures for the opponent.
+        if let Variant::Capture { target } = self.variant {
+            let winner = [self.turn, !self.turn]
+                .into_iter()
+                .find(|&c| self.captures(c) >= target);
+
+            if let Some(winner) = winner {
+                self.result = Some(GameResult::Win {
+                    winner,
+                    reason: WinReason::Captures,
+                });
+            }
+        }
+
+        self.record(Some((x, y)));
+
+        self.passes = 0;
+        self.turn = !self.turn;
+
+        Ok(response)
+    }
+
+    pub fn pass(&mut self) -> Result<()> {
+        if self.result.is_some() {
+            return Err(Error::GameOver);
+        }
+
+        if !self.pass_allowed {
+            return Err(Error::IllegalMove(IllegalMove::Pass));
+        }
+
+        self.board.pass();
+        self.record(None);
+
+        self.passes += 1;
+        self.turn = !self.turn;
+
+        if self.passes >= 2 {
+            self.result = Some(GameResult::Counting);
+        }
+
+        Ok(())
+    }
+
+    /// Add or remove stones without making a move, such as the setup stones of an sgf node.
+    /// See [Board::setup]. Whose turn it is doesn't change.
+    pub fn setup(&mut self, stones: &[((usize, usize), Stone)]) -> Result<()> {
+        if self.result.is_some() {
+            return Err(Error::GameOver);
+        }
+
+        self.board.setup(stones)
+    }
+
+    /// `color` ran out of time, so the other color wins.
+    pub fn time_out(&mut self, color: Stone) -> Result<()> {
+        if self.result.is_some() {
+            return Err(Error::GameOver);
+        }
+
+        self.result = Some(GameResult::Win {
+            winner: !color,
+            reason: WinReason::Time,
+        });
+
+        Ok(())
+    }
+
+    /// The color whose turn it is resigns.
+    pub fn resign(&mut self) -> Result<()> {
+        if self.result.is_some() {
+            return Err(Error::GameOver);
+        }
+
+        self.result = Some(GameResult::Win {
+            winner: !self.turn,
+            reason: WinReason::Resignation,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atari_go() -> Result<()> {
+        let mut game = Game::new(Board::empty(5, 5), Rules::JAPANESE, Variant::ATARI_GO);
+
+        // White's stone at (1, 0) is captured by black's third move.
+        game.play(0, 0)?;
+        game.play(1, 0)?;
+        game.play(2, 0)?;
+        game.play(4, 4)?;
+
+        assert_eq!(game.result(), None);
+
+        game.play(1, 1)?;
+
+        assert_eq!(game.captures(Stone::Black), 1);
+        assert_eq!(
+            game.result(),
+            Some(GameResult::Win {
+                winner: Stone::Black,
+                reason: WinReason::Captures,
+            })
+        );
+
+        assert_eq!(game.play(3, 3), Err(Error::GameOver));
+
+        Ok(())
+    }
+
+    #[test]
+    fn handicap_turn() -> Result<()> {
+        let mut board = Board::empty(9, 9);
+        board.place(2, 2, Stone::Black, &Rules::JAPANESE)?;
+
+        let mut game = Game::new(board, Rules::JAPANESE, Variant::Standard);
+        game.set_turn(Stone::White);
+        game.play(6, 6)?;
+
+        assert_eq!(game.board().get(6, 6), Ok(Stone::White));
+        assert_eq!(game.moves()[0].color, Stone::White);
+        assert_eq!(game.turn(), Stone::Black);
+
+        Ok(())
+    }
+
+    #[test]
+    fn suicide_captures() -> Result<()> {
+        let rules = Rules {
+            suicide_allowed: true,
+            superko: false,
+        };
+        let mut game = Game::new(Board::empty(3, 3), rules, Variant::ATARI_GO);
+
+        // * w +
+        // w + +
+        // + b b
+
+        game.play(2, 2)?;
+        game.play(1, 0)?;
+        game.play(1, 2)?;
+        game.play(0, 1)?;
+
+        // Black's suicide at * gives white its first capture.
+        game.play(0, 0)?;
+
+        assert_eq!(game.captures(Stone::White), 1);
+        assert_eq!(
+            game.result(),
+            Some(GameResult::Win {
+                winner: Stone::White,
+                reason: WinReason::Captures,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn capture_target() -> Result<()> {
+        let mut game = Game::new(
+            Board::empty(5, 5),
+            Rules::JAPANESE,
+            Variant::Capture { target: 2 },
+        );
+
+        game.play(0, 0)?;
+        game.play(1, 0)?;
+        game.play(2, 0)?;
+        game.play(4, 4)?;
+        game.play(1, 1)?;
+
+        assert_eq!(game.captures(Stone::Black), 1);
+        assert_eq!(game.result(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_pass() {
+        let mut game = Game::new(Board::empty(5, 5), Rules::JAPANESE, Variant::ATARI_GO);
+
+        assert_eq!(game.pass(), Err(Error::IllegalMove(IllegalMove::Pass)));
+        assert_eq!(game.turn(), Stone::Black);
+
+        game.pass_allowed = true;
+
+        assert_eq!(game.pass(), Ok(()));
+        assert_eq!(game.turn(), Stone::White);
+    }
+
+    #[test]
+    fn two_passes() -> Result<()> {
+        let mut game = Game::new(Board::empty(5, 5), Rules::JAPANESE, Variant::Standard);
+
+        game.pass()?;
+        game.play(2, 2)?;
+        game.pass()?;
+
+        assert_eq!(game.result(), None);
+
+        game.pass()?;
+
+        assert_eq!(game.result(), Some(GameResult::Counting));
+
+        Ok(())
+    }
+
+    #[test]
+    fn resign() -> Result<()> {
+        let mut game = Game::new(Board::empty(5, 5), Rules::JAPANESE, Variant::OneColor);
+
+        game.play(2, 2)?;
+        game.resign()?;
+
+        assert_eq!(
+            game.result(),
+            Some(GameResult::Win {
+                winner: Stone::Black,
+                reason: WinReason::Resignation,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pair_go() -> Result<()> {
+        let mut game = Game::new(Board::empty(5, 5), Rules::JAPANESE, Variant::Standard);
+        game.set_turn_order(TurnOrder::new(&["a", "c"], &["b", "d"]));
+
+        assert_eq!(game.current_player(), Some("a"));
+
+        game.play(0, 0)?;
+        game.pass()?;
+        game.play(1, 1)?;
+        game.play(2, 2)?;
+        game.play(3, 3)?;
+
+        let players: Vec<_> = game
+            .moves()
+            .iter()
+            .map(|m| m.player.as_deref().unwrap())
+            .collect();
+
+        assert_eq!(players, ["a", "b", "c", "d", "a"]);
+        assert_eq!(game.moves()[1].point, None);
+        assert_eq!(game.current_player(), Some("b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_out() -> Result<()> {
+        use crate::{Clock, ManualTime, TimeControl};
+        use core::time::Duration;
+
+        let mut game = Game::new(Board::empty(5, 5), Rules::JAPANESE, Variant::Standard);
+        let time = ManualTime::new();
+        let mut clock = Clock::new(
+            TimeControl::Absolute {
+                main: Duration::from_secs(10),
+            },
+            time.clone(),
+        );
+
+        clock.start(game.turn());
+        game.play(2, 2)?;
+        clock.press();
+
+        time.advance(Duration::from_secs(11));
+
+        if let Some(color) = clock.flagged() {
+            game.time_out(color)?;
+        }
+
+        assert_eq!(
+            game.result(),
+            Some(GameResult::Win {
+                winner: Stone::Black,
+                reason: WinReason::Time,
+            })
+        );
+
+        Ok(())
+    }
+}
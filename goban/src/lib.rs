@@ -1,9 +1,31 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::needless_return)]
 
+extern crate alloc;
+
 mod board;
+mod clock;
+mod features;
+mod game;
+mod perft;
 mod result;
 mod rule;
+mod topology;
+mod turn_order;
 
-pub use board::{Board, Group, Stone};
+pub use board::{Board, Group, PlayResponse, Stone};
+#[cfg(feature = "std")]
+pub use clock::MonotonicTime;
+pub use clock::{Clock, ManualTime, TimeControl, TimeSource};
+pub use features::{Layout, Order, Plane};
+pub use game::{Game, GameMove, GameResult, Variant, WinReason};
+pub use perft::perft;
 pub use result::{Error, Result};
 pub use rule::{IllegalMove, Rules};
+pub use topology::{Graph, Grid, GridWithHoles, Topology, Torus};
+pub use turn_order::TurnOrder;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::HashSet;
+#[cfg(feature = "std")]
+pub(crate) use std::collections::HashSet;
@@ -0,0 +1,79 @@
+use crate::{Board, Rules, Stone, Topology};
+
+/// Count the sequences of `depth` legal moves that can be played from `board`,
+/// starting with `turn` and alternating colors.
+/// Passes are not counted as moves.
+///
+/// Like perft in chess engines, this is useful to check move legality against known counts
+/// and to measure the speed of [Board::play].
+pub fn perft<T: Topology>(board: &Board<T>, rules: &Rules, turn: Stone, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let (w, h) = board.size();
+    let mut count = 0;
+
+    for y in 0..h {
+        for x in 0..w {
+            let mut next = board.clone();
+
+            if next.play(x, y, turn, rules).is_ok() {
+                count += perft(&next, rules, !turn, depth - 1);
+            }
+        }
+    }
+
+    return count;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn counts(size: usize, depth: usize) -> Vec<u64> {
+        let board = Board::empty(size, size);
+
+        (1..=depth)
+            .map(|d| perft(&board, &Rules::JAPANESE, Stone::Black, d))
+            .collect()
+    }
+
+    #[test]
+    fn one_by_one() {
+        // The only move is suicide
+        assert_eq!(counts(1, 2), vec![0, 0]);
+    }
+
+    // These counts were worked out by hand, not taken from this implementation.
+    // Every point of a 2x2 board has two neighbors, and the diagonal points don't touch.
+    //
+    // 1: black plays any of the 4 points.
+    // 2: white plays any of the 3 empty points: 4 * 3 = 12.
+    //    In 8 lines white is next to black, in 4 it is diagonal.
+    // 3: both empty points are legal for black in every line: 12 * 2 = 24.
+    //    Next to white: black either makes a two-stone group with one liberty (8 lines)
+    //    or captures white (8 lines). Diagonal: black makes a group of two (8 lines).
+    // 4: after the capture, both empty points are suicide for white.
+    //    Otherwise white takes the last point and captures black's two stones: 16.
+    // 5: two empty points, both legal for black: 16 * 2 = 32.
+    // 6: white takes the last point and captures the single black stone.
+    //    This isn't a ko, since black's stone captured nothing, and the position is new: 32.
+    #[test]
+    fn two_by_two() {
+        assert_eq!(counts(2, 6), vec![4, 12, 24, 16, 32, 32]);
+    }
+
+    // Also worked out by hand.
+    // 1-3: no move can be suicide while there are fewer than two stones of the other color:
+    //      9, 9 * 8 = 72, 72 * 7 = 504.
+    // 4: of the 504 * 6 = 3024 white moves, filling a corner whose two neighbors are black is
+    //    suicide. For each of the 4 corners, black played the two neighbors in either order
+    //    and white played one of the other 6 points: 4 * 2 * 6 = 48 lines, and 3024 - 48 = 2976.
+    #[test]
+    fn three_by_three() {
+        assert_eq!(counts(3, 4), vec![9, 72, 504, 2976]);
+    }
+}
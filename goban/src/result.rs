@@ -7,6 +7,10 @@ pub enum Error {
     CoordinatesOutOfBounds,
     /// Attempt to play a move that is illegal according to [Rules](crate::rule::Rules).
     IllegalMove(IllegalMove),
+    /// Attempt to [undo](crate::Board::undo) when no moves have been made.
+    NothingToUndo,
+    /// Attempt to play in a [Game](crate::Game) that has already ended.
+    GameOver,
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
@@ -12,6 +12,8 @@ pub enum IllegalMove {
     /// Repeating a past board state.
     /// Only applicable if [Rules::superko] is true
     SuperKo,
+    /// Passing in a [Game](crate::Game) whose [Variant](crate::Variant) doesn't allow it.
+    Pass,
 }
 
 #[derive(Clone, Copy)]
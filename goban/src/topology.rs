@@ -0,0 +1,239 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+/// The shape of a [Board](crate::Board): which points exist and which points are next to each other.
+///
+/// Points are addressed by (x, y) coordinates inside a bounding box of [Topology::size],
+/// so that every topology can share the same [Board](crate::Board) storage.
+/// Points of the bounding box that are not on the board are ignored.
+pub trait Topology: Clone + PartialEq + Hash {
+    /// The (width, height) of the bounding box containing every point.
+    fn size(&self) -> (usize, usize);
+
+    /// Whether `p` is a point on the board.
+    fn contains(&self, p: (usize, usize)) -> bool;
+
+    /// The points connected to `p`. Only called with points the topology [contains](Topology::contains).
+    fn neighbors(&self, p: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_;
+}
+
+/// The usual rectangular board, where each point is connected to the points
+/// orthogonally next to it.
+#[derive(Clone, Copy, PartialEq, Debug, Hash)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+}
+impl Topology for Grid {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn contains(&self, p: (usize, usize)) -> bool {
+        p.0 < self.width && p.1 < self.height
+    }
+
+    fn neighbors(&self, p: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (x, y) = p;
+
+        [
+            (x > 0).then(|| (x - 1, y)),
+            (y > 0).then(|| (x, y - 1)),
+            (x + 1 < self.width).then_some((x + 1, y)),
+            (y + 1 < self.height).then_some((x, y + 1)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// A rectangular board whose opposite edges are connected, so that it has no edges at all.
+#[derive(Clone, Copy, PartialEq, Debug, Hash)]
+pub struct Torus {
+    pub width: usize,
+    pub height: usize,
+}
+impl Topology for Torus {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn contains(&self, p: (usize, usize)) -> bool {
+        p.0 < self.width && p.1 < self.height
+    }
+
+    fn neighbors(&self, p: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (x, y) = p;
+        let (w, h) = (self.width, self.height);
+
+        [
+            ((x + w - 1) % w, y),
+            (x, (y + h - 1) % h),
+            ((x + 1) % w, y),
+            (x, (y + 1) % h),
+        ]
+        .into_iter()
+    }
+}
+
+/// A rectangular [Grid] with some points blocked off.
+/// Blocked points are not on the board, so they act like the edge of the board.
+#[derive(Clone, PartialEq, Debug, Hash)]
+pub struct GridWithHoles {
+    grid: Grid,
+    holes: Vec<bool>,
+}
+impl GridWithHoles {
+    pub fn new(width: usize, height: usize, holes: &[(usize, usize)]) -> Self {
+        let mut blocked = vec![false; width * height];
+
+        for &(x, y) in holes {
+            if x < width && y < height {
+                blocked[y * width + x] = true;
+            }
+        }
+
+        Self {
+            grid: Grid { width, height },
+            holes: blocked,
+        }
+    }
+}
+impl Topology for GridWithHoles {
+    fn size(&self) -> (usize, usize) {
+        self.grid.size()
+    }
+
+    fn contains(&self, p: (usize, usize)) -> bool {
+        self.grid.contains(p) && !self.holes[p.1 * self.grid.width + p.0]
+    }
+
+    fn neighbors(&self, p: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.grid.neighbors(p).filter(|&n| self.contains(n))
+    }
+}
+
+/// An arbitrary board made of numbered points and the connections between them,
+/// for hexagonal, three-dimensional or otherwise irregular boards.
+///
+/// Point `i` has the coordinates (i, 0).
+#[derive(Clone, PartialEq, Debug, Hash)]
+pub struct Graph {
+    adjacency: Vec<Vec<usize>>,
+}
+impl Graph {
+    /// A graph with `points` points, where each (a, b) in `edges` connects a and b.
+    /// Edges to points that don't exist are ignored.
+    pub fn new(points: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adjacency = vec![Vec::new(); points];
+
+        for &(a, b) in edges {
+            if a < points && b < points && a != b && !adjacency[a].contains(&b) {
+                adjacency[a].push(b);
+                adjacency[b].push(a);
+            }
+        }
+
+        Self { adjacency }
+    }
+}
+impl Topology for Graph {
+    fn size(&self) -> (usize, usize) {
+        (self.adjacency.len(), 1)
+    }
+
+    fn contains(&self, p: (usize, usize)) -> bool {
+        p.0 < self.adjacency.len() && p.1 == 0
+    }
+
+    fn neighbors(&self, p: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency[p.0].iter().map(|&i| (i, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Board, Error, Rules, Stone};
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = Grid {
+            width: 3,
+            height: 3,
+        };
+
+        assert_eq!(
+            grid.neighbors((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbors((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbors((2, 2)).collect::<Vec<_>>(),
+            vec![(1, 2), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn torus_capture_across_edges() -> crate::Result<()> {
+        let mut board = Board::with_topology(Torus {
+            width: 5,
+            height: 5,
+        });
+        let rules = Rules::JAPANESE;
+
+        // A stone in the corner has four liberties, two of them on the other side of the board.
+        board.play(0, 0, Stone::White, &rules)?;
+        assert_eq!(board.get_group(0, 0)?.liberties.len(), 4);
+
+        board.play(4, 0, Stone::Black, &rules)?;
+        board.play(0, 4, Stone::Black, &rules)?;
+        board.play(1, 0, Stone::Black, &rules)?;
+        let response = board.play(0, 1, Stone::Black, &rules)?;
+
+        assert_eq!(response.white_captures, 1);
+        assert_eq!(board.get(0, 0)?, Stone::Empty);
+
+        Ok(())
+    }
+
+    #[test]
+    fn holes_act_as_edges() -> crate::Result<()> {
+        // A 3x3 board with the center blocked off.
+        let mut board = Board::with_topology(GridWithHoles::new(3, 3, &[(1, 1)]));
+        let rules = Rules::JAPANESE;
+
+        assert_eq!(
+            board.play(1, 1, Stone::Black, &rules),
+            Err(Error::CoordinatesOutOfBounds)
+        );
+
+        board.play(1, 0, Stone::White, &rules)?;
+        assert_eq!(board.get_group(1, 0)?.liberties.len(), 2);
+
+        board.play(0, 0, Stone::Black, &rules)?;
+        let response = board.play(2, 0, Stone::Black, &rules)?;
+
+        assert_eq!(response.white_captures, 1);
+        assert_eq!(board.area_score(), (8, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn graph_capture() -> crate::Result<()> {
+        // A triangle: 0, 1 and 2 are all connected to each other.
+        let mut board = Board::with_topology(Graph::new(3, &[(0, 1), (1, 2), (2, 0)]));
+        let rules = Rules::JAPANESE;
+
+        board.play(0, 0, Stone::White, &rules)?;
+        board.play(1, 0, Stone::Black, &rules)?;
+        let response = board.play(2, 0, Stone::Black, &rules)?;
+
+        assert_eq!(response.white_captures, 1);
+        assert_eq!(board.get_group(1, 0)?.liberties, [(0, 0)].into());
+
+        Ok(())
+    }
+}
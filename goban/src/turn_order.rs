@@ -0,0 +1,61 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Stone;
+
+/// The players of each color in a team game such as pair go or rengo,
+/// in the order they play.
+///
+/// Each team takes turns through its own players, so the teams don't need to be the same size:
+/// the `n`th move of a color is played by player `n % team size` of that color.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TurnOrder {
+    pub black: Vec<String>,
+    pub white: Vec<String>,
+}
+impl TurnOrder {
+    pub fn new(black: &[&str], white: &[&str]) -> Self {
+        Self {
+            black: black.iter().map(|&s| s.into()).collect(),
+            white: white.iter().map(|&s| s.into()).collect(),
+        }
+    }
+
+    /// The players of `color`, in order.
+    pub fn team(&self, color: Stone) -> &[String] {
+        match color {
+            Stone::Black => &self.black,
+            Stone::White => &self.white,
+            Stone::Empty => &[],
+        }
+    }
+
+    /// The player making the `n`th move of `color`, counting from 0.
+    /// [None] if `color` has no players.
+    pub fn player(&self, color: Stone, n: usize) -> Option<&str> {
+        let team = self.team(color);
+
+        if team.is_empty() {
+            return None;
+        }
+
+        return Some(&team[n % team.len()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uneven_teams() {
+        let order = TurnOrder::new(&["a", "b", "c"], &["d", "e"]);
+
+        let black: Vec<_> = (0..4).map(|n| order.player(Stone::Black, n)).collect();
+        let white: Vec<_> = (0..4).map(|n| order.player(Stone::White, n)).collect();
+
+        assert_eq!(black, [Some("a"), Some("b"), Some("c"), Some("a")]);
+        assert_eq!(white, [Some("d"), Some("e"), Some("d"), Some("e")]);
+        assert_eq!(order.player(Stone::Empty, 0), None);
+    }
+}
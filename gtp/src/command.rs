@@ -0,0 +1,135 @@
+use std::fmt;
+
+/// The result of a GTP command: the response text on success, or an error message.
+pub type Response = std::result::Result<String, String>;
+
+/// A single GTP command, such as `12 play black D4`.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Command {
+    pub id: Option<u32>,
+    pub name: String,
+    pub args: Vec<String>,
+}
+impl Command {
+    pub fn new(name: &str, args: Vec<&str>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            args: args.iter().map(|&s| s.into()).collect(),
+        }
+    }
+
+    /// Parse a line of GTP input, removing comments and control characters.
+    /// Returns [None] if the line contains no command.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = match line.find('#') {
+            Some(i) => &line[..i],
+            None => line,
+        };
+
+        let cleaned: String = line
+            .chars()
+            .filter(|c| !c.is_control() || *c == '\t')
+            .map(|c| if c == '\t' { ' ' } else { c })
+            .collect();
+
+        let mut words = cleaned.split_whitespace().peekable();
+
+        let id = match words.peek()?.parse::<u32>() {
+            Ok(id) => {
+                words.next();
+                Some(id)
+            }
+            Err(_) => None,
+        };
+
+        return Some(Self {
+            id,
+            name: words.next()?.to_string(),
+            args: words.map(String::from).collect(),
+        });
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(id) = self.id {
+            write!(f, "{id} ")?;
+        }
+
+        write!(f, "{}", self.name)?;
+
+        for a in &self.args {
+            write!(f, " {a}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Format a [Response] as sent by an engine, ending with the empty line that terminates it.
+pub fn format_response(id: Option<u32>, response: &Response) -> String {
+    let (status, text) = match response {
+        Ok(s) => ('=', s),
+        Err(e) => ('?', e),
+    };
+
+    let id = id.map(|i| i.to_string()).unwrap_or_default();
+
+    if text.is_empty() {
+        return format!("{status}{id}\n\n");
+    }
+
+    return format!("{status}{id} {text}\n\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_command() {
+        assert_eq!(
+            Command::parse("12 play black D4"),
+            Some(Command {
+                id: Some(12),
+                name: String::from("play"),
+                args: vec![String::from("black"), String::from("D4")],
+            })
+        );
+
+        assert_eq!(
+            Command::parse("\tclear_board # a comment\r"),
+            Some(Command::new("clear_board", vec![]))
+        );
+    }
+
+    #[test]
+    fn parse_empty_line() {
+        assert_eq!(Command::parse(""), None);
+        assert_eq!(Command::parse("   # only a comment"), None);
+        assert_eq!(Command::parse("5"), None);
+    }
+
+    #[test]
+    fn format_command() {
+        let mut command = Command::new("play", vec!["b", "pass"]);
+        assert_eq!(format!("{command}"), "play b pass");
+
+        command.id = Some(3);
+        assert_eq!(format!("{command}"), "3 play b pass");
+    }
+
+    #[test]
+    fn format_responses() {
+        assert_eq!(format_response(None, &Ok(String::new())), "=\n\n");
+        assert_eq!(
+            format_response(Some(4), &Ok(String::from("D4"))),
+            "=4 D4\n\n"
+        );
+        assert_eq!(
+            format_response(None, &Err(String::from("illegal move"))),
+            "? illegal move\n\n"
+        );
+    }
+}
@@ -0,0 +1,396 @@
+use std::ffi::OsStr;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Stdio};
+
+use mb_goban::{Board, Rules, Stone};
+
+use crate::command::{Command, Response};
+use crate::vertex::{format_color, format_vertex, parse_vertex, Vertex};
+
+/// All the ways talking to a GTP engine can fail
+#[derive(Debug)]
+pub enum GtpError {
+    Io(io::Error),
+    /// The engine answered a command with a failure response.
+    Engine(String),
+    /// The engine sent something that is not a valid GTP response.
+    Protocol(String),
+    /// A move was illegal on the [Controller]'s own [Board].
+    Goban(mb_goban::Error),
+}
+
+impl From<io::Error> for GtpError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<mb_goban::Error> for GtpError {
+    fn from(e: mb_goban::Error) -> Self {
+        Self::Goban(e)
+    }
+}
+
+pub type GtpResult<T> = std::result::Result<T, GtpError>;
+
+/// Read one response from a GTP engine, returning its id and content.
+/// Multi-line responses are joined with `\n`.
+pub fn read_response(reader: &mut impl BufRead) -> GtpResult<(Option<u32>, Response)> {
+    let mut lines: Vec<String> = Vec::new();
+
+    loop {
+        let mut line = String::new();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Err(GtpError::Protocol(String::from("unexpected end of output")));
+        }
+
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if line.trim().is_empty() {
+            // Empty lines before the response are ignored; after it, they end it.
+            if lines.is_empty() {
+                continue;
+            }
+
+            break;
+        }
+
+        lines.push(line.to_string());
+    }
+
+    let first = lines.remove(0);
+
+    let (success, rest) = if let Some(rest) = first.strip_prefix('=') {
+        (true, rest)
+    } else if let Some(rest) = first.strip_prefix('?') {
+        (false, rest)
+    } else {
+        return Err(GtpError::Protocol(first));
+    };
+
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let id = rest[..digits].parse::<u32>().ok();
+
+    let mut text = String::from(rest[digits..].trim());
+    for l in lines {
+        text.push('\n');
+        text.push_str(&l);
+    }
+
+    if success {
+        return Ok((id, Ok(text)));
+    }
+
+    return Ok((id, Err(text)));
+}
+
+/// Drives an external GTP engine running as a subprocess,
+/// keeping a local [Board] in sync with the moves sent to and received from it.
+pub struct Controller {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u32,
+
+    board: Board,
+    rules: Rules,
+    /// Every move played since the board was last cleared, including passes.
+    moves: Vec<(Stone, Vertex)>,
+}
+impl Controller {
+    /// Start an engine, e.g. `Controller::spawn("gnugo", ["--mode", "gtp"])`.
+    pub fn spawn<I, S>(program: impl AsRef<OsStr>, args: I) -> GtpResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut child = std::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe))?;
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            next_id: 1,
+
+            board: Board::default(),
+            rules: Rules::JAPANESE,
+            moves: Vec::new(),
+        })
+    }
+
+    /// The local copy of the engine's board.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn moves(&self) -> &[(Stone, Vertex)] {
+        &self.moves
+    }
+
+    /// The [Rules] used to check moves on the local board.
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+    }
+
+    /// Send a raw command and wait for its response.
+    /// This does not update the local board.
+    pub fn send(&mut self, name: &str, args: &[&str]) -> GtpResult<String> {
+        let mut command = Command::new(name, args.to_vec());
+        command.id = Some(self.next_id);
+        self.next_id += 1;
+
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()?;
+
+        let (id, response) = read_response(&mut self.stdout)?;
+
+        if id.is_some() && id != command.id {
+            return Err(GtpError::Protocol(format!(
+                "expected response {:?}, got {:?}",
+                command.id, id
+            )));
+        }
+
+        response.map_err(GtpError::Engine)
+    }
+
+    pub fn boardsize(&mut self, size: usize) -> GtpResult<()> {
+        self.send("boardsize", &[&size.to_string()])?;
+
+        self.board = Board::empty(size, size);
+        self.moves.clear();
+
+        Ok(())
+    }
+
+    pub fn clear_board(&mut self) -> GtpResult<()> {
+        self.send("clear_board", &[])?;
+
+        let (w, h) = self.board.size();
+        self.board = Board::empty(w, h);
+        self.moves.clear();
+
+        Ok(())
+    }
+
+    pub fn komi(&mut self, komi: f32) -> GtpResult<()> {
+        self.send("komi", &[&komi.to_string()])?;
+
+        Ok(())
+    }
+
+    /// Tell the engine about a move. The move is checked on the local board before it is sent.
+    pub fn play(&mut self, color: Stone, vertex: Vertex) -> GtpResult<()> {
+        let mut board = self.board.clone();
+
+        match vertex {
+            Vertex::Point(x, y) => {
+                board.play(x, y, color, &self.rules)?;
+            }
+            Vertex::Pass => board.pass(),
+        }
+
+        let v = format_vertex(vertex, self.board.size());
+        self.send("play", &[format_color(color), &v])?;
+
+        self.board = board;
+        self.moves.push((color, vertex));
+
+        Ok(())
+    }
+
+    /// Ask the engine for a move and play it on the local board.
+    /// Returns [None] if the engine resigned.
+    pub fn genmove(&mut self, color: Stone) -> GtpResult<Option<Vertex>> {
+        let response = self.send("genmove", &[format_color(color)])?;
+
+        if response.eq_ignore_ascii_case("resign") {
+            return Ok(None);
+        }
+
+        let vertex = parse_vertex(&response, self.board.size())
+            .ok_or_else(|| GtpError::Protocol(format!("invalid vertex {response}")))?;
+
+        match vertex {
+            Vertex::Point(x, y) => {
+                self.board.play(x, y, color, &self.rules)?;
+            }
+            Vertex::Pass => self.board.pass(),
+        }
+
+        self.moves.push((color, vertex));
+
+        Ok(Some(vertex))
+    }
+
+    pub fn undo(&mut self) -> GtpResult<()> {
+        self.send("undo", &[])?;
+
+        if self.moves.pop().is_some() {
+            self.board.undo()?;
+        }
+
+        Ok(())
+    }
+
+    /// Send `quit` and wait for the engine to exit.
+    pub fn quit(mut self) -> GtpResult<()> {
+        self.send("quit", &[])?;
+        self.child.wait()?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Controller {
+    fn drop(&mut self) {
+        // Engines that were already told to quit have exited, so the error is ignored.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// How a [Match] ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    /// The given color won because the other resigned.
+    Resignation(Stone),
+    /// Both players passed. Black's area minus white's area and komi;
+    /// positive if black won.
+    Score(f32),
+    /// [Match::max_moves] was reached before the game ended.
+    MoveLimit,
+}
+
+/// The result of [Match::play].
+pub struct MatchResult {
+    pub outcome: Outcome,
+    pub moves: Vec<(Stone, Vertex)>,
+}
+
+/// Settings for a game between two engines.
+#[derive(Clone, Copy)]
+pub struct Match {
+    pub size: usize,
+    pub komi: f32,
+    /// Stop the game after this many moves, passes included.
+    pub max_moves: usize,
+}
+impl Match {
+    /// Play a game between two engines, stopping after a resignation or two passes in a row.
+    /// The game is scored with area scoring, with every stone treated as alive.
+    pub fn play(&self, black: &mut Controller, white: &mut Controller) -> GtpResult<MatchResult> {
+        for engine in [&mut *black, &mut *white] {
+            engine.boardsize(self.size)?;
+            engine.clear_board()?;
+            engine.komi(self.komi)?;
+        }
+
+        let mut turn = Stone::Black;
+        let mut passes = 0;
+
+        while black.moves().len() < self.max_moves {
+            let (player, opponent) = match turn {
+                Stone::Black => (&mut *black, &mut *white),
+                _ => (&mut *white, &mut *black),
+            };
+
+            let vertex = match player.genmove(turn)? {
+                Some(v) => v,
+                None => {
+                    return Ok(MatchResult {
+                        outcome: Outcome::Resignation(!turn),
+                        moves: black.moves().to_vec(),
+                    });
+                }
+            };
+
+            opponent.play(turn, vertex)?;
+
+            if vertex == Vertex::Pass {
+                passes += 1;
+            } else {
+                passes = 0;
+            }
+
+            if passes == 2 {
+                let (b, w) = black.board().area_score();
+
+                return Ok(MatchResult {
+                    outcome: Outcome::Score(b as f32 - w as f32 - self.komi),
+                    moves: black.moves().to_vec(),
+                });
+            }
+
+            turn = !turn;
+        }
+
+        Ok(MatchResult {
+            outcome: Outcome::MoveLimit,
+            moves: black.moves().to_vec(),
+        })
+    }
+}
+
+impl Default for Match {
+    fn default() -> Self {
+        Self {
+            size: 19,
+            komi: 6.5,
+            max_moves: 1000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_responses() {
+        let mut output = "=1 D4\n\n?2 illegal move\n\n\n= \n\n=3 A1\nB2\n\n".as_bytes();
+
+        assert_eq!(
+            read_response(&mut output).unwrap(),
+            (Some(1), Ok(String::from("D4")))
+        );
+        assert_eq!(
+            read_response(&mut output).unwrap(),
+            (Some(2), Err(String::from("illegal move")))
+        );
+        assert_eq!(
+            read_response(&mut output).unwrap(),
+            (None, Ok(String::new()))
+        );
+        assert_eq!(
+            read_response(&mut output).unwrap(),
+            (Some(3), Ok(String::from("A1\nB2")))
+        );
+    }
+
+    #[test]
+    fn read_bad_responses() {
+        assert!(matches!(
+            read_response(&mut "D4\n\n".as_bytes()),
+            Err(GtpError::Protocol(_))
+        ));
+        assert!(matches!(
+            read_response(&mut "=1 D4\n".as_bytes()),
+            Err(GtpError::Protocol(_))
+        ));
+    }
+}
@@ -0,0 +1,651 @@
+use std::io::{self, BufRead, Write};
+
+use mb_goban::{Board, Rules, Stone};
+use mb_sgf::{Replay, SgfNode};
+
+use crate::command::{format_response, Command, Response};
+use crate::vertex::{format_vertex, parse_color, parse_vertex, Vertex};
+
+/// Every command understood by [Engine].
+const COMMANDS: &[&str] = &[
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "quit",
+    "boardsize",
+    "clear_board",
+    "komi",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "final_score",
+    "final_status_list",
+    "fixed_handicap",
+    "loadsgf",
+];
+
+/// The largest board that can be described with GTP vertices.
+const MAX_SIZE: usize = 25;
+
+/// A GTP version 2 engine that plays on a [Board].
+/// All moves, including those from `genmove`, are checked with [Board::play].
+pub struct Engine {
+    board: Board,
+    rules: Rules,
+    komi: f32,
+
+    /// Every move played since the board was last cleared, including passes.
+    moves: Vec<(Stone, Vertex)>,
+    /// State of the generator used to pick moves for `genmove`.
+    seed: u64,
+}
+impl Engine {
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            board: Board::default(),
+            rules,
+            komi: 6.5,
+
+            moves: Vec::new(),
+            seed: 0,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Read commands from `input` and write responses to `output` until `quit` or the end of the input.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let command = match Command::parse(&line?) {
+                Some(c) => c,
+                None => continue,
+            };
+
+            let response = self.execute(&command);
+
+            write!(output, "{}", format_response(command.id, &response))?;
+            output.flush()?;
+
+            if command.name == "quit" {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Execute a single [Command] and return its [Response].
+    pub fn execute(&mut self, command: &Command) -> Response {
+        let args: Vec<&str> = command.args.iter().map(String::as_str).collect();
+
+        match (command.name.as_str(), args.as_slice()) {
+            ("protocol_version", []) => Ok(String::from("2")),
+            ("name", []) => Ok(String::from("mbaduk")),
+            ("version", []) => Ok(String::from(env!("CARGO_PKG_VERSION"))),
+            ("known_command", [name]) => Ok(COMMANDS.contains(name).to_string()),
+            ("list_commands", []) => Ok(COMMANDS.join("\n")),
+            ("quit", []) => Ok(String::new()),
+
+            ("boardsize", [size]) => self.boardsize(size),
+            ("clear_board", []) => {
+                self.board = Board::empty(self.board.size().0, self.board.size().1);
+                self.moves.clear();
+
+                Ok(String::new())
+            }
+            ("komi", [komi]) => {
+                self.komi = komi.parse().map_err(|_| "syntax error")?;
+
+                Ok(String::new())
+            }
+            ("play", [color, vertex]) => self.play(color, vertex),
+            ("genmove", [color]) => self.genmove(color),
+            ("undo", []) => self.undo(),
+            ("showboard", []) => Ok(self.showboard()),
+            ("final_score", []) => Ok(self.final_score()),
+            ("final_status_list", [status]) => self.final_status_list(status),
+            ("fixed_handicap", [n]) => self.fixed_handicap(n),
+            ("loadsgf", [path]) => self.loadsgf(path, None),
+            ("loadsgf", [path, move_number]) => {
+                let n = move_number.parse().map_err(|_| "syntax error")?;
+
+                self.loadsgf(path, Some(n))
+            }
+
+            (name, _) if COMMANDS.contains(&name) => Err(String::from("syntax error")),
+            _ => Err(String::from("unknown command")),
+        }
+    }
+
+    fn boardsize(&mut self, size: &str) -> Response {
+        let size: usize = size.parse().map_err(|_| "syntax error")?;
+
+        if size == 0 || size > MAX_SIZE {
+            return Err(String::from("unacceptable size"));
+        }
+
+        self.board = Board::empty(size, size);
+        self.moves.clear();
+
+        Ok(String::new())
+    }
+
+    fn play(&mut self, color: &str, vertex: &str) -> Response {
+        let color = parse_color(color).ok_or("syntax error")?;
+        let vertex = parse_vertex(vertex, self.board.size()).ok_or("syntax error")?;
+
+        match vertex {
+            Vertex::Point(x, y) => {
+                self.board
+                    .play(x, y, color, &self.rules)
+                    .map_err(|_| "illegal move")?;
+            }
+            Vertex::Pass => self.board.pass(),
+        }
+
+        self.moves.push((color, vertex));
+
+        Ok(String::new())
+    }
+
+    fn genmove(&mut self, color: &str) -> Response {
+        let color = parse_color(color).ok_or("syntax error")?;
+        let vertex = self.choose_move(color);
+
+        match vertex {
+            Vertex::Point(x, y) => {
+                self.board
+                    .play(x, y, color, &self.rules)
+                    .map_err(|_| "illegal move")?;
+            }
+            Vertex::Pass => self.board.pass(),
+        }
+
+        self.moves.push((color, vertex));
+
+        Ok(format_vertex(vertex, self.board.size()))
+    }
+
+    /// Pick a random legal move that does not fill one of `color`'s own eyes, or pass if there is none.
+    fn choose_move(&mut self, color: Stone) -> Vertex {
+        let (w, h) = self.board.size();
+        let n = w * h;
+
+        self.seed = self
+            .seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let start = (self.seed >> 33) as usize % n;
+
+        for i in 0..n {
+            let (x, y) = ((start + i) % n % w, (start + i) % n / w);
+
+            if self.board.get(x, y) != Ok(Stone::Empty) || self.is_eye(x, y, color) {
+                continue;
+            }
+
+            let mut test = self.board.clone();
+
+            if test.play(x, y, color, &self.rules).is_ok() {
+                return Vertex::Point(x, y);
+            }
+        }
+
+        return Vertex::Pass;
+    }
+
+    /// An empty point entirely surrounded by stones of the given color.
+    fn is_eye(&self, x: usize, y: usize, color: Stone) -> bool {
+        let region = match self.board.get_group(x, y) {
+            Ok(g) => g,
+            Err(_) => return false,
+        };
+
+        // For an empty point, `enemy_neighbors` holds every stone next to it.
+        region.points.len() == 1
+            && region
+                .enemy_neighbors
+                .iter()
+                .all(|p| self.board.get(p.0, p.1) == Ok(color))
+    }
+
+    fn undo(&mut self) -> Response {
+        if self.moves.pop().is_none() {
+            return Err(String::from("cannot undo"));
+        }
+
+        // A record from loadsgf may have setup stones after the move, which go with it.
+        while self.board.history().count() > self.moves.len() {
+            self.board.undo().map_err(|_| "cannot undo")?;
+        }
+
+        Ok(String::new())
+    }
+
+    fn showboard(&self) -> String {
+        let (w, h) = self.board.size();
+
+        let letters: String = (0..w)
+            .map(|x| format_vertex(Vertex::Point(x, 0), (w, h)).remove(0))
+            .map(|c| format!(" {c}"))
+            .collect();
+
+        let mut s = format!("\n   {letters}\n");
+
+        for y in 0..h {
+            s.push_str(&format!("{:>2} ", h - y));
+
+            for x in 0..w {
+                s.push(' ');
+                s.push(match self.board.get(x, y) {
+                    Ok(Stone::Black) => 'X',
+                    Ok(Stone::White) => 'O',
+                    _ => '.',
+                });
+            }
+
+            s.push_str(&format!(" {}\n", h - y));
+        }
+
+        s.push_str(&format!("   {letters}"));
+
+        return s;
+    }
+
+    /// Score the game with area scoring, treating every stone on the board as alive.
+    fn final_score(&self) -> String {
+        let (black, white) = self.board.area_score();
+        let difference = black as f32 - white as f32 - self.komi;
+
+        if difference > 0.0 {
+            return format!("B+{difference}");
+        }
+        if difference < 0.0 {
+            return format!("W+{}", -difference);
+        }
+
+        return String::from("0");
+    }
+
+    /// No life and death analysis is done, so every stone is listed as alive.
+    fn final_status_list(&self, status: &str) -> Response {
+        match status {
+            "alive" => {
+                let (w, h) = self.board.size();
+
+                let stones: Vec<String> = (0..h)
+                    .flat_map(|y| (0..w).map(move |x| (x, y)))
+                    .filter(|&(x, y)| self.board.get(x, y) != Ok(Stone::Empty))
+                    .map(|(x, y)| format_vertex(Vertex::Point(x, y), (w, h)))
+                    .collect();
+
+                Ok(stones.join(" "))
+            }
+            "dead" | "seki" => Ok(String::new()),
+
+            _ => Err(String::from("syntax error")),
+        }
+    }
+
+    fn fixed_handicap(&mut self, n: &str) -> Response {
+        let n: usize = n.parse().map_err(|_| "syntax error")?;
+
+        let (w, h) = self.board.size();
+
+        if !self.moves.is_empty() || self.board != Board::empty(w, h) {
+            return Err(String::from("board not empty"));
+        }
+
+        let points = handicap_points((w, h), n).ok_or("invalid number of stones")?;
+
+        for &(x, y) in &points {
+            self.board
+                .place(x, y, Stone::Black, &self.rules)
+                .map_err(|_| "invalid number of stones")?;
+        }
+
+        let vertices: Vec<String> = points
+            .iter()
+            .map(|&(x, y)| format_vertex(Vertex::Point(x, y), (w, h)))
+            .collect();
+
+        Ok(vertices.join(" "))
+    }
+
+    fn loadsgf(&mut self, path: &str, move_number: Option<usize>) -> Response {
+        let text = std::fs::read_to_string(path).map_err(|_| "cannot load file")?;
+
+        self.load_sgf(&text, move_number)
+            .ok_or("cannot load file")?;
+
+        Ok(String::new())
+    }
+
+    /// Replay the main line of an sgf record, stopping before `move_number` if it is given.
+    /// The setup and komi of the node holding that move still apply.
+    /// The engine is left untouched if the record can't be replayed.
+    fn load_sgf(&mut self, text: &str, move_number: Option<usize>) -> Option<()> {
+        let tree = mb_sgf::parse(text).ok()?;
+
+        let mut replay = Replay::new(self.rules);
+        let mut komi = self.komi;
+
+        for node in tree.main_line() {
+            let is_move = node.props.iter().any(|p| p.id == "B" || p.id == "W");
+
+            if let Some(km) = node.props.iter().find(|p| p.id == "KM") {
+                komi = km.values.first()?.parse().ok()?;
+            }
+
+            if is_move && move_number == Some(replay.moves().len() + 1) {
+                let setup: Vec<_> = node
+                    .props
+                    .iter()
+                    .filter(|p| p.id != "B" && p.id != "W")
+                    .cloned()
+                    .collect();
+
+                replay.apply(&SgfNode::new(setup)).ok()?;
+                break;
+            }
+
+            replay.apply(node).ok()?;
+        }
+
+        let (w, h) = replay.board().size();
+
+        if w > MAX_SIZE || h > MAX_SIZE {
+            return None;
+        }
+
+        self.moves = replay
+            .moves()
+            .iter()
+            .map(|&(color, point)| match point {
+                Some((x, y)) => (color, Vertex::Point(x, y)),
+                None => (color, Vertex::Pass),
+            })
+            .collect();
+        self.board = replay.into_board();
+        self.komi = komi;
+
+        Some(())
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new(Rules::JAPANESE)
+    }
+}
+
+/// The standard GTP handicap placement for `n` stones, if `n` is allowed on a board of this size.
+fn handicap_points(size: (usize, usize), n: usize) -> Option<Vec<(usize, usize)>> {
+    let (w, h) = size;
+
+    if w != h || w < 7 {
+        return None;
+    }
+
+    let max = if w % 2 == 1 && w > 7 { 9 } else { 4 };
+
+    if n < 2 || n > max {
+        return None;
+    }
+
+    let edge = if w >= 13 { 3 } else { 2 };
+    let (low, high, mid) = (edge, w - 1 - edge, w / 2);
+
+    // D4, Q16, D16 and Q4 on a 19x19 board
+    let corners = [(low, high), (high, low), (low, low), (high, high)];
+
+    let mut points: Vec<(usize, usize)> = corners.iter().take(n.min(4)).copied().collect();
+
+    if n >= 6 {
+        points.push((low, mid));
+        points.push((high, mid));
+    }
+
+    if n >= 8 {
+        points.push((mid, high));
+        points.push((mid, low));
+    }
+
+    if n % 2 == 1 && n >= 5 {
+        points.push((mid, mid));
+    }
+
+    return Some(points);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execute(engine: &mut Engine, line: &str) -> Response {
+        engine.execute(&Command::parse(line).unwrap())
+    }
+
+    #[test]
+    fn run_session() {
+        let input = "1 boardsize 9\n\n# comment\n2 play b E5\n3 play w E5\nquit\nname\n";
+        let mut output = Vec::new();
+
+        Engine::default()
+            .run(input.as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "=1\n\n=2\n\n?3 illegal move\n\n=\n\n"
+        );
+    }
+
+    #[test]
+    fn unknown_and_malformed_commands() {
+        let mut engine = Engine::default();
+
+        assert_eq!(
+            execute(&mut engine, "foo"),
+            Err(String::from("unknown command"))
+        );
+        assert_eq!(
+            execute(&mut engine, "play black"),
+            Err(String::from("syntax error"))
+        );
+        assert_eq!(
+            execute(&mut engine, "boardsize 26"),
+            Err(String::from("unacceptable size"))
+        );
+        assert_eq!(
+            execute(&mut engine, "known_command play"),
+            Ok(String::from("true"))
+        );
+        assert_eq!(
+            execute(&mut engine, "known_command foo"),
+            Ok(String::from("false"))
+        );
+    }
+
+    #[test]
+    fn play_and_undo() {
+        let mut engine = Engine::default();
+
+        execute(&mut engine, "boardsize 9").unwrap();
+        execute(&mut engine, "play b A9").unwrap();
+        execute(&mut engine, "play w pass").unwrap();
+
+        assert_eq!(engine.board().get(0, 0), Ok(Stone::Black));
+
+        execute(&mut engine, "undo").unwrap();
+        execute(&mut engine, "undo").unwrap();
+
+        assert!(*engine.board() == Board::empty(9, 9));
+        assert_eq!(
+            execute(&mut engine, "undo"),
+            Err(String::from("cannot undo"))
+        );
+    }
+
+    #[test]
+    fn genmove_fills_board_then_passes() {
+        let mut engine = Engine::default();
+        execute(&mut engine, "boardsize 3").unwrap();
+
+        let mut passed = false;
+
+        for _ in 0..20 {
+            let vertex = execute(&mut engine, "genmove b").unwrap();
+
+            if vertex == "pass" {
+                passed = true;
+                break;
+            }
+
+            assert!(parse_vertex(&vertex, (3, 3)).is_some());
+        }
+
+        assert!(passed);
+    }
+
+    #[test]
+    fn score() {
+        let mut engine = Engine::default();
+
+        execute(&mut engine, "boardsize 5").unwrap();
+        execute(&mut engine, "komi 0.5").unwrap();
+
+        assert_eq!(
+            execute(&mut engine, "final_score"),
+            Ok(String::from("W+0.5"))
+        );
+
+        execute(&mut engine, "play b C3").unwrap();
+
+        assert_eq!(
+            execute(&mut engine, "final_score"),
+            Ok(String::from("B+24.5"))
+        );
+        assert_eq!(
+            execute(&mut engine, "final_status_list alive"),
+            Ok(String::from("C3"))
+        );
+        assert_eq!(
+            execute(&mut engine, "final_status_list dead"),
+            Ok(String::new())
+        );
+    }
+
+    #[test]
+    fn handicap() {
+        let mut engine = Engine::default();
+
+        assert_eq!(
+            execute(&mut engine, "fixed_handicap 5"),
+            Ok(String::from("D4 Q16 D16 Q4 K10"))
+        );
+        assert_eq!(
+            execute(&mut engine, "fixed_handicap 2"),
+            Err(String::from("board not empty"))
+        );
+
+        execute(&mut engine, "boardsize 9").unwrap();
+
+        assert_eq!(
+            execute(&mut engine, "fixed_handicap 10"),
+            Err(String::from("invalid number of stones"))
+        );
+        assert_eq!(
+            execute(&mut engine, "fixed_handicap 9"),
+            Ok(String::from("C3 G7 C7 G3 C5 G5 E3 E7 E5"))
+        );
+    }
+
+    #[test]
+    fn showboard() {
+        let mut engine = Engine::default();
+
+        execute(&mut engine, "boardsize 3").unwrap();
+        execute(&mut engine, "play b A1").unwrap();
+        execute(&mut engine, "play w C3").unwrap();
+
+        assert_eq!(
+            execute(&mut engine, "showboard"),
+            Ok(String::from(
+                "\n    A B C\n 3  . . O 3\n 2  . . . 2\n 1  X . . 1\n    A B C"
+            ))
+        );
+    }
+
+    #[test]
+    fn load_sgf() {
+        let mut engine = Engine::default();
+
+        let sgf = "(;SZ[9]KM[5.5]AB[aa];W[bb];B[];W[cc](;B[dd])(;B[ee]))";
+
+        engine.load_sgf(sgf, None).unwrap();
+
+        assert_eq!(engine.board().size(), (9, 9));
+        assert_eq!(engine.board().get(0, 0), Ok(Stone::Black));
+        assert_eq!(engine.board().get(3, 3), Ok(Stone::Black));
+        assert_eq!(engine.board().get(4, 4), Ok(Stone::Empty));
+        assert_eq!(engine.komi, 5.5);
+        assert_eq!(engine.moves.len(), 4);
+
+        engine.load_sgf(sgf, Some(3)).unwrap();
+
+        assert_eq!(engine.board().get(1, 1), Ok(Stone::White));
+        assert_eq!(engine.board().get(2, 2), Ok(Stone::Empty));
+        assert_eq!(engine.moves.len(), 2);
+
+        // The setup and komi in the node of the move are kept, only the move is left out.
+        engine
+            .load_sgf("(;SZ[9];B[aa];W[bb]AB[cc]KM[7.5];B[dd])", Some(2))
+            .unwrap();
+
+        assert_eq!(engine.board().get(2, 2), Ok(Stone::Black));
+        assert_eq!(engine.board().get(1, 1), Ok(Stone::Empty));
+        assert_eq!(engine.komi, 7.5);
+        assert_eq!(engine.moves.len(), 1);
+
+        assert_eq!(engine.load_sgf("(;SZ[9];B[aa];W[aa])", None), None);
+        assert_eq!(engine.moves.len(), 1);
+    }
+
+    #[test]
+    fn undo_after_setup() {
+        let mut engine = Engine::default();
+
+        engine
+            .load_sgf("(;SZ[9]AB[aa];B[bb];AW[cc];W[dd])", None)
+            .unwrap();
+
+        assert_eq!(engine.undo(), Ok(String::new()));
+        assert_eq!(engine.board().get(3, 3), Ok(Stone::Empty));
+        assert_eq!(engine.board().get(2, 2), Ok(Stone::White));
+
+        // The white setup stone came after black's move, so it goes with it.
+        assert_eq!(engine.undo(), Ok(String::new()));
+        assert_eq!(engine.board().get(2, 2), Ok(Stone::Empty));
+        assert_eq!(engine.board().get(1, 1), Ok(Stone::Empty));
+        assert_eq!(engine.board().get(0, 0), Ok(Stone::Black));
+
+        assert!(engine.undo().is_err());
+        assert_eq!(engine.board().get(0, 0), Ok(Stone::Black));
+    }
+
+    #[test]
+    fn loadsgf_missing_file() {
+        let mut engine = Engine::default();
+
+        assert_eq!(
+            execute(&mut engine, "loadsgf /this/file/does/not/exist.sgf"),
+            Err(String::from("cannot load file"))
+        );
+    }
+}
@@ -0,0 +1,11 @@
+#![allow(clippy::needless_return)]
+
+mod command;
+mod controller;
+mod engine;
+mod vertex;
+
+pub use command::{format_response, Command, Response};
+pub use controller::{read_response, Controller, GtpError, GtpResult, Match, MatchResult, Outcome};
+pub use engine::Engine;
+pub use vertex::{format_color, format_vertex, parse_color, parse_vertex, Vertex};
@@ -0,0 +1,13 @@
+use std::io;
+
+use mb_gtp::Engine;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    if let Err(e) = Engine::default().run(stdin.lock(), stdout.lock()) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,96 @@
+use mb_goban::Stone;
+
+/// Column letters used by GTP. Note that there is no 'I'.
+const COLUMNS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+/// A point on the board or a pass, as written in GTP commands.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Vertex {
+    Pass,
+    /// Zero-indexed, starting from the top-left, like [mb_goban::Board].
+    Point(usize, usize),
+}
+
+/// Parse a GTP vertex such as `D4` or `pass` on a board of the given (width, height).
+/// GTP counts rows from the bottom of the board.
+pub fn parse_vertex(s: &str, size: (usize, usize)) -> Option<Vertex> {
+    if s.eq_ignore_ascii_case("pass") {
+        return Some(Vertex::Pass);
+    }
+
+    let mut chars = s.chars();
+
+    let letter = chars.next()?.to_ascii_uppercase();
+    let x = COLUMNS.iter().position(|&c| c as char == letter)?;
+    let row = chars.as_str().parse::<usize>().ok()?;
+
+    if x >= size.0 || row == 0 || row > size.1 {
+        return None;
+    }
+
+    return Some(Vertex::Point(x, size.1 - row));
+}
+
+/// Format a [Vertex] for a board of the given (width, height).
+pub fn format_vertex(v: Vertex, size: (usize, usize)) -> String {
+    match v {
+        Vertex::Pass => String::from("pass"),
+        Vertex::Point(x, y) => format!("{}{}", COLUMNS[x] as char, size.1 - y),
+    }
+}
+
+/// Parse a GTP color, either `b`/`black` or `w`/`white`.
+pub fn parse_color(s: &str) -> Option<Stone> {
+    match s.to_ascii_lowercase().as_str() {
+        "b" | "black" => Some(Stone::Black),
+        "w" | "white" => Some(Stone::White),
+
+        _ => None,
+    }
+}
+
+pub fn format_color(s: Stone) -> &'static str {
+    match s {
+        Stone::Black => "black",
+        Stone::White => "white",
+        Stone::Empty => "empty",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertices() {
+        assert_eq!(parse_vertex("A1", (19, 19)), Some(Vertex::Point(0, 18)));
+        assert_eq!(parse_vertex("t19", (19, 19)), Some(Vertex::Point(18, 0)));
+        assert_eq!(parse_vertex("J5", (9, 9)), Some(Vertex::Point(8, 4)));
+        assert_eq!(parse_vertex("PASS", (9, 9)), Some(Vertex::Pass));
+    }
+
+    #[test]
+    fn wrong_vertices() {
+        assert_eq!(parse_vertex("I5", (19, 19)), None);
+        assert_eq!(parse_vertex("K1", (9, 9)), None);
+        assert_eq!(parse_vertex("A0", (9, 9)), None);
+        assert_eq!(parse_vertex("A10", (9, 9)), None);
+        assert_eq!(parse_vertex("", (9, 9)), None);
+    }
+
+    #[test]
+    fn vertex_round_trip() {
+        for s in ["A1", "J9", "E5", "pass"] {
+            let v = parse_vertex(s, (9, 9)).unwrap();
+
+            assert_eq!(format_vertex(v, (9, 9)), s);
+        }
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("B"), Some(Stone::Black));
+        assert_eq!(parse_color("white"), Some(Stone::White));
+        assert_eq!(parse_color("empty"), None);
+    }
+}
@@ -0,0 +1,143 @@
+use mb_goban::{Board, Rules, Stone};
+use mb_gtp::{Controller, GtpError, Match, Outcome, Vertex};
+
+fn engine() -> Controller {
+    Controller::spawn(env!("CARGO_BIN_EXE_mb_gtp"), Vec::<&str>::new())
+        .expect("failed to spawn engine")
+}
+
+#[test]
+fn board_stays_in_sync() -> Result<(), GtpError> {
+    let mut engine = engine();
+
+    engine.boardsize(9)?;
+    engine.play(Stone::Black, Vertex::Point(4, 4))?;
+
+    let reply = engine.genmove(Stone::White)?.expect("engine resigned");
+
+    if let Vertex::Point(x, y) = reply {
+        assert_eq!(engine.board().get(x, y), Ok(Stone::White));
+    }
+
+    assert_eq!(engine.send("showboard", &[])?.matches('X').count(), 1);
+
+    engine.undo()?;
+    engine.undo()?;
+
+    assert!(*engine.board() == Board::empty(9, 9));
+
+    engine.quit()
+}
+
+/// An engine that accepts every command, so that only the local board checks the moves.
+#[cfg(unix)]
+fn accepting_engine() -> Controller {
+    Controller::spawn(
+        "sh",
+        ["-c", "while read -r line; do printf '=\\n\\n'; done"],
+    )
+    .expect("failed to spawn sh")
+}
+
+#[cfg(unix)]
+#[test]
+fn ko_retake_after_passes() -> Result<(), GtpError> {
+    let mut engine = accepting_engine();
+
+    engine.boardsize(5)?;
+    engine.set_rules(Rules {
+        suicide_allowed: false,
+        superko: false,
+    });
+
+    // + b w + +
+    // b w + w +
+    // + b w + +
+    for (x, y, color) in [
+        (1, 0, Stone::Black),
+        (2, 0, Stone::White),
+        (0, 1, Stone::Black),
+        (1, 1, Stone::White),
+        (1, 2, Stone::Black),
+        (3, 1, Stone::White),
+        (4, 4, Stone::Black),
+        (2, 2, Stone::White),
+    ] {
+        engine.play(color, Vertex::Point(x, y))?;
+    }
+
+    // Black takes the ko, both sides pass, and white may take it back.
+    engine.play(Stone::Black, Vertex::Point(2, 1))?;
+    engine.play(Stone::White, Vertex::Pass)?;
+    engine.play(Stone::Black, Vertex::Pass)?;
+    engine.play(Stone::White, Vertex::Point(1, 1))?;
+
+    assert_eq!(engine.board().get(2, 1), Ok(Stone::Empty));
+
+    // Undoing the retake and both passes leaves black's capture on the board.
+    for _ in 0..3 {
+        engine.undo()?;
+    }
+
+    assert_eq!(engine.board().get(2, 1), Ok(Stone::Black));
+    assert_eq!(engine.board().get(1, 1), Ok(Stone::Empty));
+    assert_eq!(engine.board().history().count(), engine.moves().len());
+
+    Ok(())
+}
+
+#[test]
+fn errors() -> Result<(), GtpError> {
+    let mut engine = engine();
+
+    engine.boardsize(9)?;
+    engine.play(Stone::Black, Vertex::Point(0, 0))?;
+
+    // Rejected by the local board before it is sent
+    assert!(matches!(
+        engine.play(Stone::White, Vertex::Point(0, 0)),
+        Err(GtpError::Goban(_))
+    ));
+
+    // Rejected by the engine
+    assert!(matches!(engine.boardsize(30), Err(GtpError::Engine(e)) if e == "unacceptable size"));
+    assert!(matches!(engine.send("foo", &[]), Err(GtpError::Engine(e)) if e == "unknown command"));
+
+    assert_eq!(engine.moves().len(), 1);
+
+    engine.quit()
+}
+
+#[test]
+fn engine_match() -> Result<(), GtpError> {
+    let mut black = engine();
+    let mut white = engine();
+
+    let settings = Match {
+        size: 5,
+        komi: 0.5,
+        max_moves: 300,
+    };
+
+    let result = settings.play(&mut black, &mut white)?;
+
+    assert!(!matches!(result.outcome, Outcome::Resignation(_)));
+
+    // Every move in the record is legal when replayed
+    let mut board = Board::empty(5, 5);
+
+    for (color, vertex) in &result.moves {
+        match *vertex {
+            Vertex::Point(x, y) => {
+                board.play(x, y, *color, &Rules::JAPANESE)?;
+            }
+            Vertex::Pass => board.pass(),
+        }
+    }
+
+    assert!(board == *black.board());
+    assert!(board == *white.board());
+
+    black.quit()?;
+    white.quit()
+}
@@ -0,0 +1,35 @@
+//! A stand-in for `katago analysis` that replays canned responses, used to test mb_katago.
+//!
+//! Usage: `mock_analysis_engine <responses.jsonl>`
+//!
+//! For every query read, each line of the file is sent back with its `id` replaced by the query's.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+fn main() -> io::Result<()> {
+    let path = std::env::args().nth(1).expect("no responses file given");
+    let canned = std::fs::read_to_string(path)?;
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let query: Value = serde_json::from_str(&line?)?;
+
+        for response in canned.lines().filter(|l| !l.trim().is_empty()) {
+            let mut response: Value = serde_json::from_str(response)?;
+
+            if let (Some(r), Some(id)) = (response.as_object_mut(), query.get("id")) {
+                r.insert(String::from("id"), id.clone());
+            }
+
+            writeln!(stdout, "{response}")?;
+        }
+
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Stdio};
+
+use serde::Deserialize;
+
+use crate::query::Query;
+use crate::response::{parse_response, Analysis, Response};
+
+/// All the ways talking to the analysis engine can fail
+#[derive(Debug)]
+pub enum AnalysisError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// The engine sent valid JSON that is not a valid response.
+    InvalidResponse(String),
+    /// The engine reported an error for a query.
+    Engine(String),
+}
+
+impl From<io::Error> for AnalysisError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for AnalysisError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+pub type AnalysisResult<T> = std::result::Result<T, AnalysisError>;
+
+/// Talks to a KataGo analysis engine, usually started with `katago analysis`.
+/// Queries are written to `input` and responses are read from `output`, one JSON object per line.
+pub struct AnalysisClient<W: Write, R: BufRead> {
+    input: W,
+    output: R,
+
+    /// For each query still running, its board size and how many turns are left to analyze.
+    pending: HashMap<String, ((usize, usize), usize)>,
+    child: Option<Child>,
+}
+impl AnalysisClient<ChildStdin, BufReader<ChildStdout>> {
+    /// Start an engine, e.g. `AnalysisClient::spawn("katago", ["analysis", "-config", ...])`.
+    pub fn spawn<I, S>(program: impl AsRef<OsStr>, args: I) -> AnalysisResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut child = std::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let broken = || io::Error::from(io::ErrorKind::BrokenPipe);
+        let input = child.stdin.take().ok_or_else(broken)?;
+        let output = child.stdout.take().ok_or_else(broken)?;
+
+        let mut client = Self::new(input, BufReader::new(output));
+        client.child = Some(child);
+
+        Ok(client)
+    }
+}
+impl<W: Write, R: BufRead> AnalysisClient<W, R> {
+    pub fn new(input: W, output: R) -> Self {
+        Self {
+            input,
+            output,
+
+            pending: HashMap::new(),
+            child: None,
+        }
+    }
+
+    /// Send a query without waiting for its results.
+    pub fn send(&mut self, query: &Query) -> AnalysisResult<()> {
+        writeln!(self.input, "{}", query.to_json())?;
+        self.input.flush()?;
+
+        let turns = query.analyze_turns.as_ref().map_or(1, Vec::len);
+        self.pending.insert(query.id.clone(), (query.size, turns));
+
+        Ok(())
+    }
+
+    /// Whether any sent query still has turns waiting to be analyzed.
+    pub fn is_pending(&self, id: &str) -> bool {
+        self.pending.contains_key(id)
+    }
+
+    /// Wait for the next [Response] from the engine.
+    /// Returns [None] once the engine has closed its output.
+    pub fn next_response(&mut self) -> AnalysisResult<Option<Response>> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+
+            if self.output.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct Id {
+            id: Option<String>,
+        }
+
+        let id = serde_json::from_str::<Id>(&line)?.id;
+        let size = id
+            .as_ref()
+            .and_then(|i| self.pending.get(i))
+            .map_or((19, 19), |p| p.0);
+
+        let response = parse_response(&line, size)?;
+
+        match &response {
+            Response::Analysis(a) if !a.is_during_search => {
+                let left = match self.pending.get_mut(&a.id) {
+                    Some(p) => {
+                        p.1 -= 1;
+                        p.1
+                    }
+                    None => {
+                        return Err(AnalysisError::InvalidResponse(format!(
+                            "unknown query {}",
+                            a.id
+                        )));
+                    }
+                };
+
+                if left == 0 {
+                    self.pending.remove(&a.id);
+                }
+            }
+            Response::Error { id: Some(id), .. } => {
+                self.pending.remove(id);
+            }
+
+            _ => {}
+        }
+
+        Ok(Some(response))
+    }
+
+    /// Send a query and wait for the final analysis of each of its turns, in turn order.
+    /// Partial results and responses to other queries are discarded.
+    pub fn analyze(&mut self, query: &Query) -> AnalysisResult<Vec<Analysis>> {
+        self.send(query)?;
+
+        let mut results = Vec::new();
+
+        while self.is_pending(&query.id) {
+            match self.next_response()? {
+                Some(Response::Analysis(a)) if a.id == query.id && !a.is_during_search => {
+                    results.push(a);
+                }
+                Some(Response::Error { id, message, .. }) if id.as_ref() == Some(&query.id) => {
+                    return Err(AnalysisError::Engine(message));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(AnalysisError::Io(io::Error::from(
+                        io::ErrorKind::UnexpectedEof,
+                    )));
+                }
+            }
+        }
+
+        results.sort_by_key(|a| a.turn_number);
+
+        Ok(results)
+    }
+}
+
+impl<W: Write, R: BufRead> Drop for AnalysisClient<W, R> {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_responses() {
+        let output = concat!(
+            r#"{"id":"q","turnNumber":0,"isDuringSearch":true,"moveInfos":[],"rootInfo":{"visits":1,"winrate":0.5,"scoreLead":0.0}}"#,
+            "\n\n",
+            r#"{"id":"q","turnNumber":0,"isDuringSearch":false,"moveInfos":[],"rootInfo":{"visits":9,"winrate":0.5,"scoreLead":0.0}}"#,
+            "\n",
+        );
+
+        let mut input = Vec::new();
+        let mut client = AnalysisClient::new(&mut input, output.as_bytes());
+
+        client.send(&Query::new("q", (9, 9))).unwrap();
+        assert!(client.is_pending("q"));
+
+        let partial = client.next_response().unwrap().unwrap();
+        assert!(matches!(partial, Response::Analysis(a) if a.is_during_search));
+        assert!(client.is_pending("q"));
+
+        client.next_response().unwrap().unwrap();
+        assert!(!client.is_pending("q"));
+
+        assert!(client.next_response().unwrap().is_none());
+
+        drop(client);
+        assert!(String::from_utf8(input)
+            .unwrap()
+            .starts_with(r#"{"id":"q""#));
+    }
+
+    #[test]
+    fn analyze_error() {
+        let output = r#"{"id":"q","error":"Illegal move","field":"moves"}"#;
+
+        let mut client = AnalysisClient::new(io::sink(), output.as_bytes());
+
+        assert!(matches!(
+            client.analyze(&Query::new("q", (9, 9))),
+            Err(AnalysisError::Engine(e)) if e == "Illegal move"
+        ));
+    }
+}
@@ -0,0 +1,9 @@
+#![allow(clippy::needless_return)]
+
+mod client;
+mod query;
+mod response;
+
+pub use client::{AnalysisClient, AnalysisError, AnalysisResult};
+pub use query::Query;
+pub use response::{parse_response, Analysis, MoveInfo, Ownership, Response, RootInfo};
@@ -0,0 +1,174 @@
+use mb_goban::{Board, Stone};
+use mb_gtp::{format_vertex, Vertex};
+use serde::Serialize;
+
+/// A request for KataGo to analyze a game.
+/// See KataGo's `docs/Analysis_Engine.md` for the meaning of each field.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Query {
+    /// Echoed back in every [Response](crate::Response) to this query.
+    pub id: String,
+    /// (width, height) of the board.
+    pub size: (usize, usize),
+    /// Stones on the board before the first move.
+    pub initial_stones: Vec<(Stone, (usize, usize))>,
+    pub initial_player: Option<Stone>,
+    pub moves: Vec<(Stone, Vertex)>,
+    /// Either a rule set name such as `japanese` or `tromp-taylor`.
+    pub rules: String,
+    pub komi: f32,
+    /// Turns to analyze, where turn `n` is the position after `n` moves.
+    /// If [None], only the position after all the moves is analyzed.
+    pub analyze_turns: Option<Vec<usize>>,
+    pub max_visits: Option<u32>,
+    pub include_ownership: bool,
+    /// Seconds between partial results while the search is running.
+    pub report_during_search_every: Option<f32>,
+}
+impl Query {
+    /// A query for an empty board of the given (width, height).
+    pub fn new(id: &str, size: (usize, usize)) -> Self {
+        Self {
+            id: id.into(),
+            size,
+            initial_stones: Vec::new(),
+            initial_player: None,
+            moves: Vec::new(),
+            rules: String::from("japanese"),
+            komi: 6.5,
+            analyze_turns: None,
+            max_visits: None,
+            include_ownership: false,
+            report_during_search_every: None,
+        }
+    }
+
+    /// A query for a list of moves played from an empty board.
+    pub fn from_moves(id: &str, size: (usize, usize), moves: Vec<(Stone, Vertex)>) -> Self {
+        Self {
+            moves,
+            ..Self::new(id, size)
+        }
+    }
+
+    /// A query for the position on a [Board] with `next` to play.
+    pub fn from_board(id: &str, board: &Board, next: Stone) -> Self {
+        let (w, h) = board.size();
+
+        let initial_stones = (0..h)
+            .flat_map(|y| (0..w).map(move |x| (x, y)))
+            .filter_map(|(x, y)| match board.get(x, y) {
+                Ok(Stone::Empty) | Err(_) => None,
+                Ok(s) => Some((s, (x, y))),
+            })
+            .collect();
+
+        Self {
+            initial_stones,
+            initial_player: Some(next),
+            ..Self::new(id, (w, h))
+        }
+    }
+
+    /// The query as a single line of JSON, as read by the analysis engine.
+    pub fn to_json(&self) -> String {
+        let vertex = |v: Vertex| format_vertex(v, self.size);
+
+        let raw = RawQuery {
+            id: &self.id,
+            initial_stones: self
+                .initial_stones
+                .iter()
+                .map(|&(s, (x, y))| (player(s), vertex(Vertex::Point(x, y))))
+                .collect(),
+            initial_player: self.initial_player.map(player),
+            moves: self
+                .moves
+                .iter()
+                .map(|&(s, v)| (player(s), vertex(v)))
+                .collect(),
+            rules: &self.rules,
+            komi: self.komi,
+            board_x_size: self.size.0,
+            board_y_size: self.size.1,
+            analyze_turns: self.analyze_turns.as_deref(),
+            max_visits: self.max_visits,
+            include_ownership: self.include_ownership,
+            report_during_search_every: self.report_during_search_every,
+        };
+
+        // Serializing plain structs, strings and numbers can't fail.
+        serde_json::to_string(&raw).unwrap()
+    }
+}
+
+fn player(s: Stone) -> &'static str {
+    match s {
+        Stone::White => "W",
+        _ => "B",
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RawQuery<'a> {
+    id: &'a str,
+    initial_stones: Vec<(&'static str, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    initial_player: Option<&'static str>,
+    moves: Vec<(&'static str, String)>,
+    rules: &'a str,
+    komi: f32,
+    board_x_size: usize,
+    board_y_size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    analyze_turns: Option<&'a [usize]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_visits: Option<u32>,
+    include_ownership: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report_during_search_every: Option<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_query() {
+        let mut query = Query::from_moves(
+            "a",
+            (9, 9),
+            vec![
+                (Stone::Black, Vertex::Point(4, 4)),
+                (Stone::White, Vertex::Pass),
+            ],
+        );
+        query.analyze_turns = Some(vec![0, 2]);
+        query.max_visits = Some(100);
+
+        assert_eq!(
+            query.to_json(),
+            r#"{"id":"a","initialStones":[],"moves":[["B","E5"],["W","pass"]],"rules":"japanese","komi":6.5,"boardXSize":9,"boardYSize":9,"analyzeTurns":[0,2],"maxVisits":100,"includeOwnership":false}"#
+        );
+    }
+
+    #[test]
+    fn board_query() {
+        let mut board = Board::empty(5, 5);
+        let rules = mb_goban::Rules::JAPANESE;
+
+        board.play(0, 0, Stone::Black, &rules).unwrap();
+        board.play(4, 4, Stone::White, &rules).unwrap();
+
+        let query = Query::from_board("b", &board, Stone::Black);
+
+        assert_eq!(
+            query.initial_stones,
+            vec![(Stone::Black, (0, 0)), (Stone::White, (4, 4))]
+        );
+        assert!(query
+            .to_json()
+            .contains(r#""initialStones":[["B","A5"],["W","E1"]],"initialPlayer":"B""#));
+    }
+}
@@ -0,0 +1,251 @@
+use mb_goban::Stone;
+use mb_gtp::{parse_vertex, Vertex};
+use serde::Deserialize;
+
+use crate::client::{AnalysisError, AnalysisResult};
+
+/// A line of output from the analysis engine.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Response {
+    Analysis(Analysis),
+    /// A query could not be processed. `field` names the part of the query that was wrong.
+    Error {
+        id: Option<String>,
+        message: String,
+        field: Option<String>,
+    },
+    /// A query was processed, but part of it was ignored.
+    Warning {
+        id: Option<String>,
+        message: String,
+        field: Option<String>,
+    },
+}
+
+/// The analysis of one turn of a [Query](crate::Query).
+/// Winrates and scores are from the perspective the engine is configured to report,
+/// which is the side to move by default.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Analysis {
+    pub id: String,
+    pub turn_number: usize,
+    /// True for partial results sent before the search has finished.
+    pub is_during_search: bool,
+    /// Candidate moves, best first.
+    pub moves: Vec<MoveInfo>,
+    pub root: RootInfo,
+    pub ownership: Option<Ownership>,
+}
+
+/// Statistics about a candidate move.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MoveInfo {
+    pub vertex: Vertex,
+    pub visits: u64,
+    pub winrate: f64,
+    pub score_lead: f64,
+    /// The policy network's prior for this move.
+    pub prior: f64,
+    /// The rank of this move, 0 being the best.
+    pub order: usize,
+    /// The principal variation, starting with this move.
+    pub pv: Vec<Vertex>,
+}
+
+/// Statistics about the position as a whole.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RootInfo {
+    pub visits: u64,
+    pub winrate: f64,
+    pub score_lead: f64,
+    pub current_player: Option<Stone>,
+}
+
+/// Predicted ownership of each point, from -1.0 to 1.0.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Ownership {
+    size: (usize, usize),
+    values: Vec<f64>,
+}
+impl Ownership {
+    /// Note that `x` and `y` are zero-indexed, starting from the top-left.
+    pub fn get(&self, x: usize, y: usize) -> Option<f64> {
+        if x >= self.size.0 || y >= self.size.1 {
+            return None;
+        }
+
+        return self.values.get(y * self.size.0 + x).copied();
+    }
+
+    /// All values, row by row from the top-left.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawResponse {
+    id: Option<String>,
+    error: Option<String>,
+    warning: Option<String>,
+    field: Option<String>,
+
+    turn_number: Option<usize>,
+    #[serde(default)]
+    is_during_search: bool,
+    #[serde(default)]
+    move_infos: Vec<RawMoveInfo>,
+    root_info: Option<RawRootInfo>,
+    ownership: Option<Vec<f64>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMoveInfo {
+    #[serde(rename = "move")]
+    vertex: String,
+    visits: u64,
+    winrate: f64,
+    score_lead: f64,
+    #[serde(default)]
+    prior: f64,
+    order: usize,
+    #[serde(default)]
+    pv: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawRootInfo {
+    visits: u64,
+    winrate: f64,
+    score_lead: f64,
+    current_player: Option<String>,
+}
+
+/// Parse a line of engine output for a query on a board of the given (width, height).
+pub fn parse_response(line: &str, size: (usize, usize)) -> AnalysisResult<Response> {
+    let raw: RawResponse = serde_json::from_str(line)?;
+
+    if let Some(message) = raw.error {
+        return Ok(Response::Error {
+            id: raw.id,
+            message,
+            field: raw.field,
+        });
+    }
+
+    if let Some(message) = raw.warning {
+        return Ok(Response::Warning {
+            id: raw.id,
+            message,
+            field: raw.field,
+        });
+    }
+
+    let missing = |field: &str| AnalysisError::InvalidResponse(format!("missing {field}"));
+    let vertex = |s: &str| {
+        parse_vertex(s, size)
+            .ok_or_else(|| AnalysisError::InvalidResponse(format!("invalid move {s}")))
+    };
+
+    let mut moves = Vec::new();
+
+    for m in raw.move_infos {
+        moves.push(MoveInfo {
+            vertex: vertex(&m.vertex)?,
+            visits: m.visits,
+            winrate: m.winrate,
+            score_lead: m.score_lead,
+            prior: m.prior,
+            order: m.order,
+            pv: m
+                .pv
+                .iter()
+                .map(|s| vertex(s))
+                .collect::<AnalysisResult<_>>()?,
+        });
+    }
+
+    let root = raw.root_info.ok_or_else(|| missing("rootInfo"))?;
+
+    let ownership = match raw.ownership {
+        Some(values) if values.len() != size.0 * size.1 => {
+            return Err(AnalysisError::InvalidResponse(String::from(
+                "ownership does not match the board size",
+            )));
+        }
+        Some(values) => Some(Ownership { size, values }),
+        None => None,
+    };
+
+    Ok(Response::Analysis(Analysis {
+        id: raw.id.ok_or_else(|| missing("id"))?,
+        turn_number: raw.turn_number.ok_or_else(|| missing("turnNumber"))?,
+        is_during_search: raw.is_during_search,
+        moves,
+        root: RootInfo {
+            visits: root.visits,
+            winrate: root.winrate,
+            score_lead: root.score_lead,
+            current_player: match root.current_player.as_deref() {
+                Some("B") => Some(Stone::Black),
+                Some("W") => Some(Stone::White),
+                _ => None,
+            },
+        },
+        ownership,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_analysis() {
+        let line = r#"{"id":"q","turnNumber":2,"isDuringSearch":false,"moveInfos":[{"move":"C3","visits":30,"winrate":0.6,"scoreLead":1.5,"prior":0.25,"order":0,"pv":["C3","pass"]}],"rootInfo":{"visits":40,"winrate":0.55,"scoreLead":1.0,"currentPlayer":"W"},"ownership":[1,1,1,0.5,-0.5,0,0,0,0]}"#;
+
+        let response = parse_response(line, (3, 3)).unwrap();
+
+        let analysis = match response {
+            Response::Analysis(a) => a,
+            _ => panic!("expected an analysis"),
+        };
+
+        assert_eq!(analysis.turn_number, 2);
+        assert_eq!(analysis.root.current_player, Some(Stone::White));
+        assert_eq!(
+            analysis.moves[0].pv,
+            vec![Vertex::Point(2, 0), Vertex::Pass]
+        );
+        assert_eq!(analysis.ownership.unwrap().get(1, 1), Some(-0.5));
+    }
+
+    #[test]
+    fn parse_error() {
+        let line = r#"{"error":"Could not parse move","field":"moves","id":"q"}"#;
+
+        assert_eq!(
+            parse_response(line, (19, 19)).unwrap(),
+            Response::Error {
+                id: Some(String::from("q")),
+                message: String::from("Could not parse move"),
+                field: Some(String::from("moves")),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(matches!(
+            parse_response("not json", (19, 19)),
+            Err(AnalysisError::Json(_))
+        ));
+        assert!(matches!(
+            parse_response(r#"{"id":"q","turnNumber":0}"#, (19, 19)),
+            Err(AnalysisError::InvalidResponse(_))
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+use mb_goban::Stone;
+use mb_gtp::Vertex;
+use mb_katago::{AnalysisClient, Query, Response};
+
+fn mock_engine(
+) -> AnalysisClient<std::process::ChildStdin, std::io::BufReader<std::process::ChildStdout>> {
+    let responses = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/responses.jsonl");
+
+    AnalysisClient::spawn(env!("CARGO_BIN_EXE_mock_analysis_engine"), [responses])
+        .expect("failed to spawn engine")
+}
+
+#[test]
+fn analyze_turns() {
+    let mut client = mock_engine();
+
+    let mut query = Query::from_moves("game", (5, 5), vec![(Stone::Black, Vertex::Point(2, 2))]);
+    query.analyze_turns = Some(vec![0, 1]);
+    query.include_ownership = true;
+
+    let results = client.analyze(&query).unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].turn_number, 0);
+    assert_eq!(results[0].root.current_player, Some(Stone::Black));
+
+    let last = &results[1];
+
+    assert_eq!(last.id, "game");
+    assert_eq!(last.root.visits, 101);
+    assert_eq!(last.moves[0].vertex, Vertex::Point(2, 2));
+    assert_eq!(
+        last.moves[0].pv,
+        vec![Vertex::Point(2, 2), Vertex::Point(1, 3), Vertex::Pass]
+    );
+    assert_eq!(last.moves[1].score_lead, -1.0);
+    assert_eq!(last.ownership.as_ref().unwrap().get(0, 1), Some(-1.0));
+}
+
+#[test]
+fn stream_every_response() {
+    let mut client = mock_engine();
+
+    client.send(&Query::new("stream", (5, 5))).unwrap();
+
+    let mut responses = Vec::new();
+
+    while client.is_pending("stream") {
+        responses.push(client.next_response().unwrap().unwrap());
+    }
+
+    assert!(matches!(&responses[0], Response::Analysis(a) if a.is_during_search));
+    assert!(matches!(&responses[1], Response::Warning { field: Some(f), .. } if f == "komi"));
+    assert!(matches!(&responses[2], Response::Analysis(a) if !a.is_during_search));
+}
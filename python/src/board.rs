@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+
+use mb_goban::{Board, Error, Group, Layout, Plane, Rules, Stone};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(
+    mbaduk,
+    IllegalMoveError,
+    PyValueError,
+    "A move that is illegal according to the Rules."
+);
+
+fn to_py_err(e: Error) -> PyErr {
+    match e {
+        Error::CoordinatesOutOfBounds => PyIndexError::new_err("coordinates out of bounds"),
+        Error::IllegalMove(m) => IllegalMoveError::new_err(format!("{m:?}")),
+        e => PyValueError::new_err(format!("{e:?}")),
+    }
+}
+
+#[pyclass(name = "Stone", eq, eq_int, frozen)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PyStone {
+    Empty,
+    Black,
+    White,
+}
+impl From<Stone> for PyStone {
+    fn from(s: Stone) -> Self {
+        match s {
+            Stone::Empty => Self::Empty,
+            Stone::Black => Self::Black,
+            Stone::White => Self::White,
+        }
+    }
+}
+impl From<PyStone> for Stone {
+    fn from(s: PyStone) -> Self {
+        match s {
+            PyStone::Empty => Self::Empty,
+            PyStone::Black => Self::Black,
+            PyStone::White => Self::White,
+        }
+    }
+}
+
+#[pyclass(name = "Rules", get_all, set_all)]
+#[derive(Clone, Copy)]
+pub struct PyRules {
+    pub suicide_allowed: bool,
+    pub superko: bool,
+}
+#[pymethods]
+impl PyRules {
+    #[new]
+    #[pyo3(signature = (suicide_allowed = false, superko = true))]
+    fn new(suicide_allowed: bool, superko: bool) -> Self {
+        Self {
+            suicide_allowed,
+            superko,
+        }
+    }
+
+    #[staticmethod]
+    fn japanese() -> Self {
+        Rules::JAPANESE.into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Rules(suicide_allowed={}, superko={})",
+            py_bool(self.suicide_allowed),
+            py_bool(self.superko)
+        )
+    }
+}
+impl From<Rules> for PyRules {
+    fn from(r: Rules) -> Self {
+        Self {
+            suicide_allowed: r.suicide_allowed,
+            superko: r.superko,
+        }
+    }
+}
+impl From<PyRules> for Rules {
+    fn from(r: PyRules) -> Self {
+        Self {
+            suicide_allowed: r.suicide_allowed,
+            superko: r.superko,
+        }
+    }
+}
+
+fn py_bool(b: bool) -> &'static str {
+    if b {
+        "True"
+    } else {
+        "False"
+    }
+}
+
+/// A [Group], with its points as sets of (x, y) tuples.
+#[pyclass(name = "Group", get_all, frozen)]
+pub struct PyGroup {
+    pub color: PyStone,
+    pub points: HashSet<(usize, usize)>,
+    pub liberties: HashSet<(usize, usize)>,
+    pub enemy_neighbors: HashSet<(usize, usize)>,
+}
+impl From<Group> for PyGroup {
+    fn from(g: Group) -> Self {
+        Self {
+            color: g.color.into(),
+            points: g.points,
+            liberties: g.liberties,
+            enemy_neighbors: g.enemy_neighbors,
+        }
+    }
+}
+
+/// The board as three planes of height × width, row by row:
+/// black stones, white stones and empty points, with 1 where the plane applies.
+pub fn planes(board: &Board) -> Vec<u8> {
+    let layout = Layout::new(vec![
+        Plane::Stones(Stone::Black),
+        Plane::Stones(Stone::White),
+        Plane::Stones(Stone::Empty),
+    ]);
+
+    return layout.to_u8(board, Stone::Black);
+}
+
+#[pyclass(name = "Board")]
+#[derive(Clone)]
+pub struct PyBoard {
+    pub board: Board,
+}
+#[pymethods]
+impl PyBoard {
+    /// A square board if `height` is not given.
+    #[new]
+    #[pyo3(signature = (width = 19, height = None))]
+    fn new(width: usize, height: Option<usize>) -> Self {
+        Self {
+            board: Board::empty(width, height.unwrap_or(width)),
+        }
+    }
+
+    /// (width, height)
+    #[getter]
+    fn size(&self) -> (usize, usize) {
+        self.board.size()
+    }
+
+    fn get(&self, x: usize, y: usize) -> PyResult<PyStone> {
+        Ok(self.board.get(x, y).map_err(to_py_err)?.into())
+    }
+
+    /// Play a move, returning the number of (black, white) stones captured.
+    /// Raises IllegalMoveError if the move is illegal.
+    #[pyo3(signature = (x, y, stone, rules = None))]
+    fn play(
+        &mut self,
+        x: usize,
+        y: usize,
+        stone: PyStone,
+        rules: Option<PyRules>,
+    ) -> PyResult<(u16, u16)> {
+        let rules = rules.map_or(Rules::JAPANESE, Rules::from);
+        let response = self
+            .board
+            .play(x, y, stone.into(), &rules)
+            .map_err(to_py_err)?;
+
+        Ok((response.black_captures, response.white_captures))
+    }
+
+    /// Put a stone on the board, or remove one with Stone.Empty, ignoring the ko rules.
+    #[pyo3(signature = (x, y, stone, rules = None))]
+    fn place(
+        &mut self,
+        x: usize,
+        y: usize,
+        stone: PyStone,
+        rules: Option<PyRules>,
+    ) -> PyResult<(u16, u16)> {
+        let rules = rules.map_or(Rules::JAPANESE, Rules::from);
+        let response = self
+            .board
+            .place(x, y, stone.into(), &rules)
+            .map_err(to_py_err)?;
+
+        Ok((response.black_captures, response.white_captures))
+    }
+
+    fn undo(&mut self) -> PyResult<()> {
+        self.board.undo().map_err(to_py_err)
+    }
+
+    fn get_group(&self, x: usize, y: usize) -> PyResult<PyGroup> {
+        Ok(self.board.get_group(x, y).map_err(to_py_err)?.into())
+    }
+
+    /// The Tromp-Taylor area of (black, white).
+    fn area_score(&self) -> (usize, usize) {
+        self.board.area_score()
+    }
+
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+
+    /// The board as a numpy array of shape (3, height, width) and dtype uint8:
+    /// black stones, white stones and empty points.
+    #[cfg(feature = "numpy")]
+    fn planes<'py>(&self, py: Python<'py>) -> Bound<'py, numpy::PyArray3<u8>> {
+        use numpy::IntoPyArray;
+
+        let (w, h) = self.board.size();
+
+        // The length always matches the shape.
+        numpy::ndarray::Array3::from_shape_vec((3, h, w), planes(&self.board))
+            .unwrap()
+            .into_pyarray(py)
+    }
+
+    /// The standard neural network input planes for `turn` to move, with the last `history` moves,
+    /// as a numpy array of shape (planes, height, width) and dtype float32.
+    /// See `mb_goban::Layout::standard` for the planes.
+    #[cfg(feature = "numpy")]
+    #[pyo3(signature = (turn, history = 8))]
+    fn features<'py>(
+        &self,
+        py: Python<'py>,
+        turn: PyStone,
+        history: usize,
+    ) -> Bound<'py, numpy::PyArray3<f32>> {
+        use numpy::IntoPyArray;
+
+        let layout = Layout::standard(history);
+        let (w, h) = self.board.size();
+
+        numpy::ndarray::Array3::from_shape_vec(
+            (layout.planes.len(), h, w),
+            layout.to_f32(&self.board, turn.into()),
+        )
+        .unwrap()
+        .into_pyarray(py)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_planes() -> mb_goban::Result<()> {
+        let mut board = Board::empty(3, 2);
+
+        board.play(0, 0, Stone::Black, &Rules::JAPANESE)?;
+        board.play(2, 1, Stone::White, &Rules::JAPANESE)?;
+
+        #[rustfmt::skip]
+        assert_eq!(
+            planes(&board),
+            [
+                1, 0, 0,
+                0, 0, 0,
+
+                0, 0, 0,
+                0, 0, 1,
+
+                0, 1, 1,
+                1, 1, 0,
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+#![allow(clippy::needless_return)]
+
+//! Python bindings for [mb_goban] and [mb_sgf], built with PyO3 and maturin.
+
+use pyo3::prelude::*;
+
+mod board;
+mod sgf;
+
+pub use board::{IllegalMoveError, PyBoard, PyGroup, PyRules, PyStone};
+pub use sgf::{PySgfNode, PySgfTree};
+
+#[pymodule]
+fn mbaduk(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyStone>()?;
+    m.add_class::<PyRules>()?;
+    m.add_class::<PyBoard>()?;
+    m.add_class::<PyGroup>()?;
+    m.add_class::<PySgfTree>()?;
+    m.add_class::<PySgfNode>()?;
+    m.add_function(wrap_pyfunction!(sgf::parse, m)?)?;
+    m.add("IllegalMoveError", m.py().get_type::<IllegalMoveError>())?;
+
+    Ok(())
+}
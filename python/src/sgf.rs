@@ -0,0 +1,88 @@
+use mb_sgf::util::unescape;
+use mb_sgf::{SgfNode, SgfTree};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+#[pyclass(name = "SgfNode", frozen)]
+#[derive(Clone)]
+pub struct PySgfNode {
+    pub node: SgfNode,
+}
+#[pymethods]
+impl PySgfNode {
+    /// Every property as an (id, values) tuple with the values unescaped, in file order.
+    #[getter]
+    fn props(&self) -> Vec<(String, Vec<String>)> {
+        self.node
+            .props
+            .iter()
+            .map(|p| (p.id.clone(), p.values.iter().map(|v| unescape(v)).collect()))
+            .collect()
+    }
+
+    /// The unescaped values of property `id`, or None if the node doesn't have it.
+    fn get(&self, id: &str) -> Option<Vec<String>> {
+        self.node
+            .props
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.values.iter().map(|v| unescape(v)).collect())
+    }
+
+    fn __str__(&self) -> String {
+        self.node.to_string()
+    }
+}
+
+#[pyclass(name = "SgfTree", frozen)]
+#[derive(Clone)]
+pub struct PySgfTree {
+    pub tree: SgfTree,
+}
+#[pymethods]
+impl PySgfTree {
+    /// The sequence of nodes before the tree branches.
+    #[getter]
+    fn nodes(&self) -> Vec<PySgfNode> {
+        self.tree
+            .nodes
+            .iter()
+            .map(|n| PySgfNode { node: n.clone() })
+            .collect()
+    }
+
+    /// The variations after [PySgfTree::nodes], the first being the main line.
+    #[getter]
+    fn children(&self) -> Vec<PySgfTree> {
+        self.tree
+            .children
+            .iter()
+            .map(|t| PySgfTree { tree: t.clone() })
+            .collect()
+    }
+
+    /// Every node of the main line, following the first variation at each branch.
+    fn main_line(&self) -> Vec<PySgfNode> {
+        let mut nodes = Vec::new();
+        let mut tree = Some(&self.tree);
+
+        while let Some(t) = tree {
+            nodes.extend(t.nodes.iter().map(|n| PySgfNode { node: n.clone() }));
+            tree = t.children.first();
+        }
+
+        return nodes;
+    }
+
+    fn __str__(&self) -> String {
+        self.tree.to_string()
+    }
+}
+
+/// Parse SGF text. Raises ValueError if it is not valid SGF.
+#[pyfunction]
+pub fn parse(text: &str) -> PyResult<PySgfTree> {
+    let tree = mb_sgf::parse(text).map_err(|e| PyValueError::new_err(format!("{e:?}")))?;
+
+    Ok(PySgfTree { tree })
+}
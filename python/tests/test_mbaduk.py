@@ -0,0 +1,96 @@
+# Run with `maturin develop && pytest` from the python directory.
+import pytest
+
+import mbaduk
+from mbaduk import Board, Rules, Stone
+
+
+def test_capture():
+    board = Board(5)
+
+    board.play(1, 0, Stone.White)
+    board.play(0, 0, Stone.Black)
+    board.play(2, 0, Stone.Black)
+
+    assert board.play(1, 1, Stone.Black) == (0, 1)
+    assert board.get(1, 0) == Stone.Empty
+
+
+def test_illegal_moves():
+    board = Board(5, 5)
+    board.play(2, 2, Stone.Black)
+
+    with pytest.raises(mbaduk.IllegalMoveError):
+        board.play(2, 2, Stone.White)
+
+    with pytest.raises(IndexError):
+        board.play(9, 9, Stone.White)
+
+
+def test_suicide_rules():
+    board = Board(3, 3)
+    board.play(1, 0, Stone.Black)
+    board.play(0, 1, Stone.Black)
+
+    with pytest.raises(mbaduk.IllegalMoveError):
+        board.play(0, 0, Stone.White)
+
+    # Suiciding a single stone repeats the position, so superko has to be off too.
+    rules = Rules(suicide_allowed=True, superko=False)
+
+    assert board.play(0, 0, Stone.White, rules) == (0, 1)
+
+
+def test_group():
+    board = Board(5)
+    board.play(0, 0, Stone.Black)
+    board.play(1, 0, Stone.Black)
+
+    group = board.get_group(0, 0)
+
+    assert group.color == Stone.Black
+    assert group.points == {(0, 0), (1, 0)}
+    assert group.liberties == {(2, 0), (0, 1), (1, 1)}
+
+
+def test_planes():
+    np = pytest.importorskip("numpy")
+
+    board = Board(3, 2)
+    board.play(0, 0, Stone.Black)
+
+    planes = board.planes()
+
+    assert planes.shape == (3, 2, 3)
+    assert planes.dtype == np.uint8
+    assert planes[0, 0, 0] == 1
+    assert planes[2].sum() == 5
+
+
+def test_features():
+    np = pytest.importorskip("numpy")
+
+    board = Board(5)
+    board.play(2, 2, Stone.Black)
+
+    features = board.features(Stone.White, history=2)
+
+    assert features.shape == (9, 5, 5)
+    assert features.dtype == np.float32
+    # The opponent's stone, with 4 liberties, was the last move.
+    assert features[1, 2, 2] == 1.0
+    assert features[4, 2, 2] == 1.0
+    assert features[6, 2, 2] == 1.0
+
+
+def test_sgf():
+    tree = mbaduk.parse("(;SZ[9]PB[Ann];B[aa](;W[bb])(;W[cc]))")
+
+    assert tree.nodes[0].get("PB") == ["Ann"]
+    assert tree.nodes[0].props == [("SZ", ["9"]), ("PB", ["Ann"])]
+    assert len(tree.children) == 2
+    assert [str(n) for n in tree.main_line()] == [";SZ[9]PB[Ann]", ";B[aa]", ";W[bb]"]
+    assert str(tree) == "(;SZ[9]PB[Ann];B[aa](;W[bb])(;W[cc]))"
+
+    with pytest.raises(ValueError):
+        mbaduk.parse("(;B[aa]")
@@ -0,0 +1,302 @@
+use crate::tree::{SgfNode, SgfTree};
+
+/// A position in an [SgfTree], for stepping through its nodes.
+///
+/// Each node is addressed by its path: the index of the child taken at each node,
+/// starting from the root. The root itself is `[]` and the main line is all zeroes.
+/// Nodes in the middle of a sequence have exactly one child.
+#[derive(Clone, Debug)]
+pub struct Cursor<'a> {
+    tree: &'a SgfTree,
+    /// The sequence holding each node from the root to the current node, and the node's index in it.
+    stack: Vec<(&'a SgfTree, usize)>,
+    path: Vec<usize>,
+}
+impl<'a> Cursor<'a> {
+    /// A cursor at the root node of `tree`. [None] if the tree has no nodes.
+    pub fn new(tree: &'a SgfTree) -> Option<Self> {
+        tree.nodes.first()?;
+
+        Some(Self {
+            tree,
+            stack: vec![(tree, 0)],
+            path: Vec::new(),
+        })
+    }
+
+    pub fn node(&self) -> &'a SgfNode {
+        let &(sequence, index) = self.stack.last().unwrap();
+
+        &sequence.nodes[index]
+    }
+
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// The children of the current node, the first being the main line.
+    pub fn children(&self) -> Vec<&'a SgfNode> {
+        let &(sequence, index) = self.stack.last().unwrap();
+
+        if index + 1 < sequence.nodes.len() {
+            return vec![&sequence.nodes[index + 1]];
+        }
+
+        sequence
+            .children
+            .iter()
+            .filter_map(|c| c.nodes.first())
+            .collect()
+    }
+
+    /// Move to child `i` of the current node.
+    /// Returns the new node, or [None] without moving if there is no such child.
+    pub fn child(&mut self, i: usize) -> Option<&'a SgfNode> {
+        let &(sequence, index) = self.stack.last().unwrap();
+
+        let next = if index + 1 < sequence.nodes.len() {
+            (i == 0).then_some((sequence, index + 1))?
+        } else {
+            let child = sequence.children.get(i)?;
+            child.nodes.first()?;
+
+            (child, 0)
+        };
+
+        self.stack.push(next);
+        self.path.push(i);
+
+        Some(self.node())
+    }
+
+    /// Move forward along the main line from the current node. The same as `child(0)`.
+    // Not an Iterator, since the cursor can go back again at the end of a line.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&'a SgfNode> {
+        self.child(0)
+    }
+
+    /// Move back to the parent of the current node.
+    /// Returns the parent, or [None] without moving at the root.
+    pub fn prev(&mut self) -> Option<&'a SgfNode> {
+        if self.is_root() {
+            return None;
+        }
+
+        self.stack.pop();
+        self.path.pop();
+
+        Some(self.node())
+    }
+
+    /// The parent of the current node, without moving. [None] at the root.
+    pub fn parent(&self) -> Option<&'a SgfNode> {
+        let &(sequence, index) = self.stack.get(self.stack.len().checked_sub(2)?)?;
+
+        Some(&sequence.nodes[index])
+    }
+
+    /// The current node and its siblings, in order: the children of the parent node.
+    /// Just the current node at the root.
+    pub fn siblings(&self) -> Vec<&'a SgfNode> {
+        let mut parent = self.clone();
+
+        match parent.prev() {
+            Some(_) => parent.children(),
+            None => vec![self.node()],
+        }
+    }
+
+    /// The index of the current node among its [siblings](Cursor::siblings).
+    pub fn sibling_index(&self) -> usize {
+        self.path.last().copied().unwrap_or_default()
+    }
+
+    /// Move to sibling `i` of the current node, to switch to another variation.
+    /// Returns the new node, or [None] without moving if there is no such sibling.
+    pub fn sibling(&mut self, i: usize) -> Option<&'a SgfNode> {
+        let mut parent = self.clone();
+        parent.prev()?;
+        parent.child(i)?;
+
+        *self = parent;
+
+        Some(self.node())
+    }
+
+    /// Move to the node at `path`, from the root.
+    /// Returns the new node, or [None] without moving if there is no such node.
+    pub fn goto(&mut self, path: &[usize]) -> Option<&'a SgfNode> {
+        let mut cursor = Self::new(self.tree)?;
+
+        for &i in path {
+            cursor.child(i)?;
+        }
+
+        *self = cursor;
+
+        Some(self.node())
+    }
+}
+
+/// The nodes of the main line of an [SgfTree], from the root.
+#[derive(Clone, Debug)]
+pub struct MainLine<'a> {
+    sequence: Option<&'a SgfTree>,
+    index: usize,
+}
+impl<'a> Iterator for MainLine<'a> {
+    type Item = &'a SgfNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let sequence = self.sequence?;
+
+            if let Some(node) = sequence.nodes.get(self.index) {
+                self.index += 1;
+
+                return Some(node);
+            }
+
+            self.sequence = sequence.children.first();
+            self.index = 0;
+        }
+    }
+}
+
+impl SgfTree {
+    /// A [Cursor] at the root node. [None] if the tree has no nodes.
+    pub fn cursor(&self) -> Option<Cursor<'_>> {
+        Cursor::new(self)
+    }
+
+    /// The nodes of the main line, following the first child of each node.
+    pub fn main_line(&self) -> MainLine<'_> {
+        MainLine {
+            sequence: Some(self),
+            index: 0,
+        }
+    }
+
+    /// The [path](Cursor) of the first node, in depth-first order, for which `f` is true.
+    pub fn find_path(&self, mut f: impl FnMut(&SgfNode) -> bool) -> Option<Vec<usize>> {
+        fn search(
+            sequence: &SgfTree,
+            path: &mut Vec<usize>,
+            f: &mut impl FnMut(&SgfNode) -> bool,
+        ) -> bool {
+            for (i, node) in sequence.nodes.iter().enumerate() {
+                if i > 0 {
+                    path.push(0);
+                }
+
+                if f(node) {
+                    return true;
+                }
+            }
+
+            for (i, child) in sequence.children.iter().enumerate() {
+                path.push(i);
+
+                if search(child, path, f) {
+                    return true;
+                }
+
+                path.truncate(path.len() - child.nodes.len());
+            }
+
+            false
+        }
+
+        let mut path = Vec::new();
+
+        match search(self, &mut path, &mut f) {
+            true => Some(path),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, SgfProp};
+
+    fn has(node: &SgfNode, id: &str, value: &str) -> bool {
+        node.props.contains(&SgfProp::new(id, value))
+    }
+
+    // Root, B[aa], then W[bb] with B[cc], or W[dd] with B[ee] and B[ff].
+    const TREE: &str = "(;SZ[9];B[aa](;W[bb];B[cc])(;W[dd](;B[ee])(;B[ff])))";
+
+    #[test]
+    fn navigation() {
+        let tree = parse(TREE).unwrap();
+        let mut cursor = tree.cursor().unwrap();
+
+        assert!(cursor.is_root());
+        assert!(cursor.prev().is_none());
+        assert!(cursor.parent().is_none());
+
+        assert!(has(cursor.next().unwrap(), "B", "aa"));
+        assert_eq!(cursor.children().len(), 2);
+        assert!(has(cursor.parent().unwrap(), "SZ", "9"));
+
+        assert!(has(cursor.child(1).unwrap(), "W", "dd"));
+        assert!(cursor.child(2).is_none());
+        assert!(has(cursor.child(1).unwrap(), "B", "ff"));
+        assert_eq!(cursor.path(), [0, 1, 1]);
+        assert_eq!(cursor.sibling_index(), 1);
+        assert_eq!(cursor.siblings().len(), 2);
+
+        assert!(has(cursor.sibling(0).unwrap(), "B", "ee"));
+        assert!(cursor.sibling(2).is_none());
+        assert_eq!(cursor.path(), [0, 1, 0]);
+
+        assert!(has(cursor.prev().unwrap(), "W", "dd"));
+        assert!(has(cursor.sibling(0).unwrap(), "W", "bb"));
+
+        // In the middle of a sequence there is only one child.
+        assert!(cursor.child(1).is_none());
+        assert!(has(cursor.next().unwrap(), "B", "cc"));
+        assert!(cursor.next().is_none());
+        assert_eq!(cursor.siblings().len(), 1);
+
+        assert!(has(cursor.goto(&[0, 1, 1]).unwrap(), "B", "ff"));
+        assert!(cursor.goto(&[0, 2]).is_none());
+        assert_eq!(cursor.path(), [0, 1, 1]);
+    }
+
+    #[test]
+    fn main_line() {
+        let tree = parse(TREE).unwrap();
+
+        let moves: Vec<_> = tree
+            .main_line()
+            .flat_map(|n| &n.props)
+            .map(|p| p.to_string())
+            .collect();
+
+        assert_eq!(moves, ["SZ[9]", "B[aa]", "W[bb]", "B[cc]"]);
+    }
+
+    #[test]
+    fn find_path() {
+        let tree = parse(TREE).unwrap();
+
+        assert_eq!(tree.find_path(|n| has(n, "SZ", "9")), Some(vec![]));
+        assert_eq!(tree.find_path(|n| has(n, "B", "cc")), Some(vec![0, 0, 0]));
+        assert_eq!(tree.find_path(|n| has(n, "W", "dd")), Some(vec![0, 1]));
+        assert_eq!(tree.find_path(|n| has(n, "B", "ff")), Some(vec![0, 1, 1]));
+        assert_eq!(tree.find_path(|n| has(n, "B", "zz")), None);
+
+        let path = tree.find_path(|n| has(n, "B", "ee")).unwrap();
+        let mut cursor = tree.cursor().unwrap();
+
+        assert!(has(cursor.goto(&path).unwrap(), "B", "ee"));
+    }
+}
@@ -0,0 +1,231 @@
+use crate::tree::{SgfNode, SgfTree};
+
+/// Editing by [path](crate::Cursor).
+///
+/// Sequences are split and merged as needed, so the grouping of nodes into sequences never
+/// matters: paths to nodes that weren't moved stay the same, and an edit never leaves
+/// a sequence with a single child.
+impl SgfTree {
+    /// Add `node` as the last child of the node at `path`, continuing the line if the node
+    /// has no children yet and starting a new variation otherwise.
+    /// Returns the path of the new node, or [None] if there is no node at `path`.
+    pub fn add_child(&mut self, path: &[usize], node: SgfNode) -> Option<Vec<usize>> {
+        let (sequence, index) = self.locate_mut(path)?;
+
+        split(sequence, index);
+
+        let mut new_path = path.to_vec();
+        new_path.push(sequence.children.len());
+
+        if sequence.children.is_empty() {
+            sequence.nodes.push(node);
+        } else {
+            sequence.children.push(SgfTree::new([node], []));
+        }
+
+        return Some(new_path);
+    }
+
+    /// Remove the node at `path` and everything after it.
+    /// Returns the removed nodes, or [None] if there is no node at `path` or it is the root.
+    pub fn delete(&mut self, path: &[usize]) -> Option<SgfTree> {
+        let (&i, parent) = path.split_last()?;
+        let (sequence, index) = self.locate_mut(parent)?;
+
+        let in_sequence = index + 1 < sequence.nodes.len();
+
+        if (in_sequence && i != 0) || (!in_sequence && i >= sequence.children.len()) {
+            return None;
+        }
+
+        split(sequence, index);
+
+        let removed = sequence.children.remove(i);
+        merge(sequence);
+
+        return Some(removed);
+    }
+
+    /// Move the node at `path` to position `to` among its siblings.
+    /// Returns the new path of the node, or [None] if there is no node at `path`,
+    /// it is the root, or there is no position `to`.
+    pub fn move_child(&mut self, path: &[usize], to: usize) -> Option<Vec<usize>> {
+        let (&i, parent) = path.split_last()?;
+        let (sequence, index) = self.locate_mut(parent)?;
+
+        // A node in the middle of a sequence is an only child, so it can't move.
+        if index + 1 < sequence.nodes.len() {
+            return (i == 0 && to == 0).then(|| path.to_vec());
+        }
+
+        if i >= sequence.children.len() || to >= sequence.children.len() {
+            return None;
+        }
+
+        let child = sequence.children.remove(i);
+        sequence.children.insert(to, child);
+
+        let mut new_path = parent.to_vec();
+        new_path.push(to);
+
+        return Some(new_path);
+    }
+
+    /// Make the line leading to the node at `path` the main line, by moving each node on the
+    /// way to the front of its siblings.
+    /// Returns the new path of the node, or [None] if there is no node at `path`.
+    pub fn promote(&mut self, path: &[usize]) -> Option<Vec<usize>> {
+        self.locate_mut(path)?;
+
+        for depth in 1..=path.len() {
+            let mut step = vec![0; depth - 1];
+            step.push(path[depth - 1]);
+
+            self.move_child(&step, 0)?;
+        }
+
+        return Some(vec![0; path.len()]);
+    }
+
+    /// Merge every sequence that has a single child with that child.
+    /// This doesn't change the path of any node.
+    pub fn normalize(&mut self) {
+        merge(self);
+
+        for child in &mut self.children {
+            child.normalize();
+        }
+    }
+
+    /// The sequence holding the node at `path`, and the index of the node in it.
+    fn locate_mut(&mut self, path: &[usize]) -> Option<(&mut SgfTree, usize)> {
+        let mut sequence = self;
+        let mut index = 0;
+
+        sequence.nodes.first()?;
+
+        for &i in path {
+            if index + 1 < sequence.nodes.len() {
+                if i != 0 {
+                    return None;
+                }
+
+                index += 1;
+            } else {
+                sequence = sequence.children.get_mut(i)?;
+                sequence.nodes.first()?;
+
+                index = 0;
+            }
+        }
+
+        return Some((sequence, index));
+    }
+}
+
+/// Split `sequence` after node `index`, so that the nodes after it become its only child.
+fn split(sequence: &mut SgfTree, index: usize) {
+    if index + 1 >= sequence.nodes.len() {
+        return;
+    }
+
+    let rest = SgfTree {
+        nodes: sequence.nodes.split_off(index + 1),
+        children: std::mem::take(&mut sequence.children),
+    };
+
+    sequence.children.push(rest);
+}
+
+/// Undo [split]: while `sequence` has a single child, append the child's nodes to it.
+fn merge(sequence: &mut SgfTree) {
+    while sequence.children.len() == 1 {
+        let child = sequence.children.pop().unwrap();
+
+        sequence.nodes.extend(child.nodes);
+        sequence.children = child.children;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, SgfProp};
+
+    fn node(id: &str, value: &str) -> SgfNode {
+        SgfNode::new([SgfProp::new(id, value)])
+    }
+
+    #[test]
+    fn add_child() {
+        let mut tree = parse("(;SZ[9];B[aa];W[bb])").unwrap();
+
+        // Continue the line at the end, start a variation in the middle.
+        assert_eq!(
+            tree.add_child(&[0, 0], node("B", "cc")),
+            Some(vec![0, 0, 0])
+        );
+        assert_eq!(tree.add_child(&[0], node("W", "dd")), Some(vec![0, 1]));
+        assert_eq!(tree.add_child(&[0], node("W", "ee")), Some(vec![0, 2]));
+        assert_eq!(tree.add_child(&[0, 3], node("W", "ff")), None);
+
+        assert_eq!(
+            tree.to_string(),
+            "(;SZ[9];B[aa](;W[bb];B[cc])(;W[dd])(;W[ee]))"
+        );
+
+        let mut cursor = tree.cursor().unwrap();
+        assert_eq!(cursor.goto(&[0, 1]), Some(&node("W", "dd")));
+    }
+
+    #[test]
+    fn delete() {
+        let mut tree = parse("(;SZ[9];B[aa](;W[bb];B[cc])(;W[dd]))").unwrap();
+
+        assert_eq!(tree.delete(&[]), None);
+        assert_eq!(tree.delete(&[0, 2]), None);
+
+        // Deleting the middle of a sequence cuts it short.
+        assert_eq!(tree.delete(&[0, 0, 0]), Some(parse("(;B[cc])").unwrap()));
+        assert_eq!(tree.to_string(), "(;SZ[9];B[aa](;W[bb])(;W[dd]))");
+
+        // With only one variation left, it is merged back into the main line.
+        assert_eq!(tree.delete(&[0, 0]), Some(parse("(;W[bb])").unwrap()));
+        assert_eq!(tree.to_string(), "(;SZ[9];B[aa];W[dd])");
+    }
+
+    #[test]
+    fn move_and_promote() {
+        let mut tree = parse("(;SZ[9](;B[aa])(;B[bb](;W[cc])(;W[dd]))(;B[ee]))").unwrap();
+
+        assert_eq!(tree.move_child(&[2], 0), Some(vec![0]));
+        assert_eq!(
+            tree.to_string(),
+            "(;SZ[9](;B[ee])(;B[aa])(;B[bb](;W[cc])(;W[dd])))"
+        );
+        assert_eq!(tree.move_child(&[2], 3), None);
+
+        assert_eq!(tree.promote(&[2, 1]), Some(vec![0, 0]));
+        assert_eq!(
+            tree.to_string(),
+            "(;SZ[9](;B[bb](;W[dd])(;W[cc]))(;B[ee])(;B[aa]))"
+        );
+        assert_eq!(tree.promote(&[3]), None);
+
+        // The only child in a sequence is already in front.
+        let mut tree = parse("(;SZ[9];B[aa])").unwrap();
+        assert_eq!(tree.promote(&[0]), Some(vec![0]));
+        assert_eq!(tree.move_child(&[0], 1), None);
+    }
+
+    #[test]
+    fn normalize() {
+        let mut tree = parse("(;SZ[9](;B[aa](;W[bb](;B[cc])(;B[dd]))))").unwrap();
+        let before = tree.find_path(|n| *n == node("B", "dd"));
+
+        tree.normalize();
+
+        assert_eq!(tree.to_string(), "(;SZ[9];B[aa];W[bb](;B[cc])(;B[dd]))");
+        assert_eq!(tree.find_path(|n| *n == node("B", "dd")), before);
+    }
+}
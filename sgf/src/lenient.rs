@@ -0,0 +1,405 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::parse::{check_values, Location};
+use crate::tree::{Collection, SgfNode, SgfProp, SgfTree};
+use crate::util::normalize_id;
+use crate::{ParseError, ParseResult};
+
+/// Something wrong with sgf text that [parse_lenient] worked around.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Warning {
+    pub location: Location,
+    pub kind: WarningKind,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum WarningKind {
+    /// Text that isn't part of the sgf structure, which was skipped.
+    SkippedText(String),
+    /// A property id with lowercase letters, and the id it was turned into.
+    LowercaseId(String, String),
+    /// A property id with no values, which was left out.
+    MissingValue(String),
+    /// A value with no closing `]` before the end of the text.
+    UnclosedValue,
+    /// A game tree with no closing `)` before the end of the text.
+    UnclosedTree,
+    /// A game tree with no nodes, which was left out.
+    EmptyTree,
+    /// A move, setup or SZ[] property with a value that doesn't fit it.
+    /// The property is kept as it is.
+    InvalidValue(String, ParseError),
+}
+
+/// Parse sgf text that may be slightly broken, as many files in the wild are,
+/// skipping or repairing what doesn't fit and returning a [Warning] for each problem.
+///
+/// Only fails if there is no game tree at all.
+pub fn parse_lenient(input: &str) -> ParseResult<(Collection, Vec<Warning>)> {
+    let mut parser = Lenient {
+        chars: input.chars().peekable(),
+        location: Location { line: 1, column: 1 },
+        warnings: Vec::new(),
+    };
+
+    let mut collection = Collection::default();
+
+    loop {
+        parser.skip_whitespace();
+
+        match parser.chars.peek() {
+            None => break,
+
+            Some('(') => {
+                let start = parser.location;
+                parser.bump();
+
+                if let Some(mut t) = parser.tree(start) {
+                    t.upgrade_ff3();
+                    collection.trees.push(t);
+                }
+            }
+
+            Some(_) => parser.skip(|c| c == '('),
+        }
+    }
+
+    if collection.trees.is_empty() {
+        return Err(ParseError::NoGameTree);
+    }
+
+    return Ok((collection, parser.warnings));
+}
+
+struct Lenient<'a> {
+    chars: Peekable<Chars<'a>>,
+    location: Location,
+    warnings: Vec<Warning>,
+}
+impl Lenient<'_> {
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+
+        if c == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn warn(&mut self, location: Location, kind: WarningKind) {
+        self.warnings.push(Warning { location, kind });
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    /// Skip at least one character, up to the next one for which `stop` is true.
+    fn skip(&mut self, stop: impl Fn(char) -> bool) {
+        let start = self.location;
+        let mut text = String::new();
+
+        while let Some(c) = self.bump() {
+            text.push(c);
+
+            if self.chars.peek().is_none_or(|&c| stop(c)) {
+                break;
+            }
+        }
+
+        self.warn(start, WarningKind::SkippedText(text.trim_end().into()));
+    }
+
+    /// The rest of a game tree after its `(`, which was at `start`.
+    fn tree(&mut self, start: Location) -> Option<SgfTree> {
+        let mut tree = SgfTree::default();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.chars.peek() {
+                None => {
+                    self.warn(start, WarningKind::UnclosedTree);
+                    break;
+                }
+
+                Some(';') if tree.children.is_empty() => {
+                    self.bump();
+                    tree.nodes.push(self.node());
+                }
+
+                Some('(') => {
+                    let child_start = self.location;
+                    self.bump();
+
+                    if let Some(t) = self.tree(child_start) {
+                        tree.children.push(t);
+                    }
+                }
+
+                Some(')') => {
+                    self.bump();
+                    break;
+                }
+
+                // A node after the variations has nowhere to go.
+                Some(';') => {
+                    let node_start = self.location;
+                    self.bump();
+
+                    let node = self.node();
+                    self.warn(node_start, WarningKind::SkippedText(format!("{node}")));
+                }
+
+                Some(_) => self.skip(|c| matches!(c, ';' | '(' | ')')),
+            }
+        }
+
+        if tree.nodes.is_empty() {
+            self.warn(start, WarningKind::EmptyTree);
+
+            // `((;B[aa]))` can still be kept.
+            if tree.children.len() == 1 {
+                return tree.children.pop();
+            }
+
+            return None;
+        }
+
+        return Some(tree);
+    }
+
+    /// The rest of a node after its `;`.
+    fn node(&mut self) -> SgfNode {
+        let mut node = SgfNode::default();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.chars.peek() {
+                None | Some(';' | '(' | ')') => break,
+
+                Some(c) if c.is_alphabetic() => {
+                    if let Some(p) = self.prop() {
+                        node.props.push(p);
+                    }
+                }
+
+                Some(_) => self.skip(|c| c.is_alphabetic() || matches!(c, ';' | '(' | ')')),
+            }
+        }
+
+        return node;
+    }
+
+    fn prop(&mut self) -> Option<SgfProp> {
+        let start = self.location;
+        let mut id = String::new();
+
+        while let Some(&c) = self.chars.peek().filter(|c| c.is_alphabetic()) {
+            id.push(c);
+            self.bump();
+        }
+
+        if id.chars().any(|c| !c.is_ascii_uppercase()) {
+            let fixed = normalize_id(&id);
+
+            self.warn(start, WarningKind::LowercaseId(id, fixed.clone()));
+            id = fixed;
+        }
+
+        let mut values = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            if self.chars.peek() != Some(&'[') {
+                break;
+            }
+
+            let value_start = self.location;
+            self.bump();
+
+            values.push(self.value(value_start));
+        }
+
+        if values.is_empty() {
+            self.warn(start, WarningKind::MissingValue(id));
+            return None;
+        }
+
+        let prop = SgfProp { id, values };
+
+        if let Err(e) = check_values(&prop) {
+            self.warn(start, WarningKind::InvalidValue(prop.id.clone(), e));
+        }
+
+        Some(prop)
+    }
+
+    /// The rest of a value after its `[`, which was at `start`, with its escapes kept.
+    fn value(&mut self, start: Location) -> String {
+        let mut value = String::new();
+
+        loop {
+            match self.bump() {
+                None => {
+                    self.warn(start, WarningKind::UnclosedValue);
+                    break;
+                }
+
+                Some(']') => break,
+
+                Some('\\') => {
+                    value.push('\\');
+                    value.extend(self.bump());
+                }
+
+                Some(c) => value.push(c),
+            }
+        }
+
+        return value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, parse_collection};
+
+    fn at(line: usize, column: usize, kind: WarningKind) -> Warning {
+        Warning {
+            location: Location { line, column },
+            kind,
+        }
+    }
+
+    #[test]
+    fn valid_text() {
+        let text = "(;FF[4]C[a \\] b];B[aa](;W[bb])(;W[cc]))\n(;B[dd])";
+
+        let (collection, warnings) = parse_lenient(text).unwrap();
+
+        assert_eq!(
+            collection.trees,
+            [parse(text).unwrap(), parse("(;B[dd])").unwrap()]
+        );
+        assert_eq!(warnings, []);
+    }
+
+    #[test]
+    fn recovery() {
+        let text = "junk (;SZ[9]\nb[aa];AddWhite[bb]KO;W[zz?] ? ;B[cc]";
+
+        let (collection, warnings) = parse_lenient(text).unwrap();
+
+        // The invalid W[] is kept, so the strict parser can't make the expected tree.
+        assert_eq!(collection.trees.len(), 1);
+        assert_eq!(
+            collection.trees[0].to_string(),
+            "(;SZ[9]B[aa];AW[bb];W[zz?];B[cc])"
+        );
+        assert_eq!(
+            warnings,
+            [
+                at(1, 1, WarningKind::SkippedText("junk".into())),
+                at(2, 1, WarningKind::LowercaseId("b".into(), "B".into())),
+                at(
+                    2,
+                    7,
+                    WarningKind::LowercaseId("AddWhite".into(), "AW".into())
+                ),
+                at(2, 19, WarningKind::MissingValue("KO".into())),
+                at(
+                    2,
+                    22,
+                    WarningKind::InvalidValue("W".into(), ParseError::CoordinateParseError)
+                ),
+                at(2, 29, WarningKind::SkippedText("?".into())),
+                at(1, 6, WarningKind::UnclosedTree),
+            ]
+        );
+    }
+
+    #[test]
+    fn same_as_strict() {
+        let texts = [
+            "(;FF[4]GM[1]SZ[19]AB[aa:bb][cc]C[a \\] b \\\\];B[dd](;W[ee]C[x\\:y])(;W[ff];B[]))",
+            "(;GN[one];B[aa])\n(;GN[two](;W[bb])(;W[cc]))\n",
+            "( ; AP[a\\:b:1.0] LB[aa:one][bb:two]\n ; W[tt] ; B[ab]\r\n)",
+            "(;SZ[5:9]AE[aa]DD[]C[soft\\\nbreak])",
+        ];
+
+        for text in texts {
+            let (collection, warnings) = parse_lenient(text).unwrap();
+
+            assert_eq!(Ok(collection), parse_collection(text), "{text}");
+            assert_eq!(warnings, [], "{text}");
+        }
+    }
+
+    #[test]
+    fn same_errors_as_strict() {
+        let texts = [
+            "(;SZ[9];B[a1])",
+            "(;SZ[9]\n;W[aa]AB[zz:a])",
+            "(;C[x]\n  SZ[nine])",
+            "(;B[aa][bb])",
+        ];
+
+        for text in texts {
+            let error = parse(text).unwrap_err();
+            let (_, warnings) = parse_lenient(text).unwrap();
+
+            let ParseError::InvalidProperty {
+                location,
+                id,
+                error,
+            } = error
+            else {
+                panic!("{text}: {error:?}");
+            };
+
+            assert_eq!(
+                warnings,
+                [Warning {
+                    location,
+                    kind: WarningKind::InvalidValue(id, *error)
+                }],
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn broken_structure() {
+        let (collection, warnings) = parse_lenient("(;B[aa](;W[bb]);B[cc])()(;C[open").unwrap();
+
+        assert_eq!(
+            collection.trees,
+            [
+                parse("(;B[aa](;W[bb]))").unwrap(),
+                parse("(;C[open])").unwrap()
+            ]
+        );
+        assert_eq!(
+            warnings,
+            [
+                at(1, 16, WarningKind::SkippedText(";B[cc]".into())),
+                at(1, 23, WarningKind::EmptyTree),
+                at(1, 28, WarningKind::UnclosedValue),
+                at(1, 25, WarningKind::UnclosedTree),
+            ]
+        );
+
+        assert_eq!(parse_lenient("no sgf here"), Err(ParseError::NoGameTree));
+    }
+}
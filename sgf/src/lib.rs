@@ -1,8 +1,19 @@
 #![allow(clippy::needless_return)]
 
+mod cursor;
+mod edit;
+mod lenient;
 mod parse;
+mod property;
+mod record;
+mod replay;
 mod tree;
 pub mod util;
 
-pub use parse::{parse, ParseError, ParseResult};
-pub use tree::{SgfNode, SgfProp, SgfTree};
+pub use cursor::{Cursor, MainLine};
+pub use lenient::{parse_lenient, Warning, WarningKind};
+pub use parse::{parse, parse_collection, Location, ParseError, ParseResult};
+pub use property::{Double, Point, Property};
+pub use record::{clock_props, record_game, GameInfo};
+pub use replay::{nodes_on_path, replay, replay_main_line, NodeError, Replay, ReplayError};
+pub use tree::{Collection, SgfNode, SgfProp, SgfTree};
@@ -1,5 +1,9 @@
-use crate::tree::{SgfNode, SgfProp, SgfTree};
+use std::fmt;
 
+use crate::tree::{Collection, SgfNode, SgfProp, SgfTree};
+use crate::util::{normalize_id, parse_board_size, parse_coords, parse_point_list};
+
+use pest::error::LineColLocation;
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
@@ -7,14 +11,82 @@ use pest_derive::Parser;
 /// All the ways [mb_sgf](crate) can fail
 #[derive(Clone, Debug, PartialEq)]
 pub enum ParseError {
-    Pest(pest::error::Error<Rule>),
+    /// Boxed, since the pest error is much larger than the other variants.
+    Pest(Box<pest::error::Error<Rule>>),
     CoordinateParseError,
     IntParse(std::num::ParseIntError),
+    FloatParse(std::num::ParseFloatError),
+    /// A property value that doesn't fit the property.
+    InvalidValue(String),
+    /// The id of a property with the wrong number of values.
+    WrongValueCount(String),
+    /// A move, setup or SZ[] property whose value doesn't fit it,
+    /// with the location of the property in the text.
+    InvalidProperty {
+        location: Location,
+        id: String,
+        error: Box<ParseError>,
+    },
+    /// The text has no game tree at all, even after skipping anything invalid.
+    NoGameTree,
+}
+impl ParseError {
+    /// Where in the text the error is, for errors in the structure of the sgf text
+    /// and in the values of move, setup and SZ[] properties.
+    /// Errors from [Property::from_prop](crate::Property::from_prop) have no location.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Self::Pest(e) => {
+                let (line, column) = match e.line_col {
+                    LineColLocation::Pos(p) => p,
+                    LineColLocation::Span(start, _) => start,
+                };
+
+                Some(Location { line, column })
+            }
+
+            Self::InvalidProperty { location, .. } => Some(*location),
+
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(l) = self.location() {
+            write!(f, "{l}: ")?;
+        }
+
+        match self {
+            Self::Pest(e) => write!(f, "{}", e.variant.message()),
+            Self::CoordinateParseError => write!(f, "invalid coordinates"),
+            Self::IntParse(e) => write!(f, "invalid number: {e}"),
+            Self::FloatParse(e) => write!(f, "invalid real number: {e}"),
+            Self::InvalidValue(v) => write!(f, "invalid value {v}"),
+            Self::WrongValueCount(id) => write!(f, "wrong number of values for {id}"),
+            Self::InvalidProperty { id, error, .. } => write!(f, "{id}: {error}"),
+            Self::NoGameTree => write!(f, "no game tree"),
+        }
+    }
+}
+
+/// A position in sgf text. Lines and columns count from 1, and columns count characters.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
 }
 
 impl From<pest::error::Error<Rule>> for ParseError {
     fn from(e: pest::error::Error<Rule>) -> Self {
-        Self::Pest(e)
+        Self::Pest(Box::new(e))
     }
 }
 
@@ -24,17 +96,50 @@ impl From<std::num::ParseIntError> for ParseError {
     }
 }
 
+impl From<std::num::ParseFloatError> for ParseError {
+    fn from(e: std::num::ParseFloatError) -> Self {
+        Self::FloatParse(e)
+    }
+}
+
 pub type ParseResult<T> = std::result::Result<T, ParseError>;
 
 #[derive(Parser)]
 #[grammar = "sgf.pest"]
 struct SgfParser;
 
-/// Parse an sgf string to an [SgfTree]
+/// Parse an sgf string to an [SgfTree].
+/// Only the first tree is parsed; use [parse_collection] for files with several games.
 pub fn parse(input: &str) -> ParseResult<SgfTree> {
     let tree_rule = SgfParser::parse(Rule::tree, input)?.next().unwrap();
 
-    parse_tree(tree_rule)
+    let mut tree = parse_tree(tree_rule)?;
+    tree.upgrade_ff3();
+
+    Ok(tree)
+}
+
+/// Parse a whole sgf file, which may contain several game trees, to a [Collection]
+pub fn parse_collection(input: &str) -> ParseResult<Collection> {
+    let collection_rule = SgfParser::parse(Rule::collection, input)?.next().unwrap();
+
+    let mut collection = Collection::default();
+
+    for i in collection_rule.into_inner() {
+        match i.as_rule() {
+            Rule::tree => {
+                let mut tree = parse_tree(i)?;
+                tree.upgrade_ff3();
+
+                collection.trees.push(tree);
+            }
+            Rule::EOI => {}
+
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(collection)
 }
 
 fn parse_tree(r: Pair<'_, Rule>) -> ParseResult<SgfTree> {
@@ -67,23 +172,47 @@ fn parse_node(n: Pair<'_, Rule>) -> ParseResult<SgfNode> {
 }
 
 fn parse_prop(p: Pair<'_, Rule>) -> ParseResult<SgfProp> {
+    let (line, column) = p.line_col();
     let mut prop = SgfProp::default();
 
     for i in p.into_inner() {
         match i.as_rule() {
-            Rule::prop_id => prop.id = String::from(i.as_str()),
-            Rule::prop_value => prop.values.push(String::from(i.as_str())),
+            Rule::prop_id => prop.id = normalize_id(i.as_str()),
+            Rule::prop_value => prop.values.push(i.as_str().into()),
 
             _ => unreachable!(),
         }
     }
 
+    check_values(&prop).map_err(|e| ParseError::InvalidProperty {
+        location: Location { line, column },
+        id: prop.id.clone(),
+        error: Box::new(e),
+    })?;
+
     Ok(prop)
 }
 
+/// Check the values of the properties that decide the position: moves, setup stones and SZ[].
+/// Other values are only checked by [Property::from_prop](crate::Property::from_prop).
+pub(crate) fn check_values(prop: &SgfProp) -> ParseResult<()> {
+    let values = &prop.values;
+
+    match prop.id.as_str() {
+        "B" | "W" if values.len() == 1 && values[0].is_empty() => Ok(()),
+        "B" | "W" if values.len() == 1 => parse_coords(&values[0]).map(|_| ()),
+        "B" | "W" | "SZ" if values.len() != 1 => Err(ParseError::WrongValueCount(prop.id.clone())),
+        "SZ" => parse_board_size(&values[0]).map(|_| ()),
+        "AB" | "AW" | "AE" => parse_point_list(values).map(|_| ()),
+
+        _ => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Property;
 
     #[test]
     fn parse_game_comment() {
@@ -142,4 +271,93 @@ mod tests {
         assert_eq!(parse(sgf_data).unwrap(), tree);
         assert_eq!(parse(sgf_data_with_spaces).unwrap(), tree);
     }
+
+    #[test]
+    fn parse_many_trees() {
+        let sgf_data = "(;GN[one];B[aa])\n(;GN[two](;W[bb])(;W[cc]))\n";
+
+        let collection = parse_collection(sgf_data).unwrap();
+
+        assert_eq!(collection.trees.len(), 2);
+        assert_eq!(collection.trees[0], parse("(;GN[one];B[aa])").unwrap());
+        assert_eq!(collection.trees[1].children.len(), 2);
+
+        assert_eq!(
+            parse_collection(&collection.to_string()).unwrap(),
+            collection
+        );
+
+        assert!(parse_collection("").is_err());
+        assert!(parse_collection("(;B[aa]) junk").is_err());
+    }
+
+    #[test]
+    fn parse_ff3() {
+        let tree = parse("(;FF[3]AddBlack[aa]sz[9]Comment[old];B[bb]L[cc][dd]M[ee])").unwrap();
+
+        assert_eq!(
+            tree.to_string(),
+            "(;FF[3]AB[aa]SZ[9]C[old];B[bb]LB[cc:a][dd:b]MA[ee])"
+        );
+
+        // Without FF[] the file is FF[1], and variations are upgraded too.
+        assert_eq!(
+            parse("(;SZ[9];L[aa](;M[bb])(;L[cc]))").unwrap().to_string(),
+            "(;SZ[9];LB[aa:a](;MA[bb])(;LB[cc:a]))"
+        );
+
+        // L[] and M[] mean nothing in FF[4], so they are kept as unknown properties.
+        assert_eq!(
+            parse("(;FF[4];L[aa]M[bb])").unwrap().to_string(),
+            "(;FF[4];L[aa]M[bb])"
+        );
+
+        let collection = parse_collection("(;FF[4];L[aa])(;FF[1];L[aa])").unwrap();
+
+        assert_eq!(
+            collection.to_string(),
+            "(;FF[4];L[aa])\n(;FF[1];LB[aa:a])\n"
+        );
+    }
+
+    #[test]
+    fn error_location() {
+        let error = parse("(;B[aa]\n;W[bb]x)").unwrap_err();
+
+        assert_eq!(error.location(), Some(Location { line: 2, column: 7 }));
+        assert!(error.to_string().starts_with("line 2, column 7: "));
+
+        assert_eq!(ParseError::CoordinateParseError.location(), None);
+
+        let error = parse("(;SZ[9]\n;B[aa]  W[a1])").unwrap_err();
+
+        assert_eq!(error.location(), Some(Location { line: 2, column: 9 }));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 9: W: invalid coordinates"
+        );
+    }
+
+    #[test]
+    fn parse_escapes() {
+        let sgf_data = "(;C[see a\\]b, \\\\ and \\x]GC[one \\\nline])";
+
+        let tree = parse(sgf_data).unwrap();
+
+        assert_eq!(
+            tree.nodes[0].properties(),
+            Ok(vec![
+                Property::Comment("see a]b, \\ and x".into()),
+                Property::GameComment("one line".into())
+            ])
+        );
+
+        // The values keep their escapes, so the text is written back as it was.
+        assert_eq!(tree.to_string(), sgf_data);
+
+        // `\:` stays apart from the `:` that splits a composed value.
+        let tree = parse("(;AP[a\\:b:1.0])").unwrap();
+
+        assert_eq!(tree.to_string(), "(;AP[a\\:b:1.0])");
+    }
 }
@@ -0,0 +1,681 @@
+use mb_goban::Stone;
+
+use crate::tree::SgfProp;
+use crate::util::{
+    compress_points, escape, format_board_size, format_composed, format_coords, parse_board_size,
+    parse_composed, parse_coords, parse_point_list, unescape,
+};
+use crate::{ParseError, ParseResult};
+
+/// A point on the board, (x, y) from the top-left.
+pub type Point = (usize, usize);
+
+/// The value of properties such as GB[] and TE[]: 1 for normal, 2 for emphasized.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Double {
+    Normal,
+    Emphasized,
+}
+
+/// A property of the FF[4] specification with its value parsed,
+/// or [Property::Unknown] for properties of other games, applications or versions.
+///
+/// Text values are unescaped, and SimpleText values have their line breaks turned into spaces.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Property {
+    // Move properties.
+    /// B[], [None] for a pass. `tt` is kept as a point, since it is only a pass on boards
+    /// up to 19x19; see [parse_move](crate::util::parse_move).
+    Black(Option<Point>),
+    /// W[], [None] for a pass, like [Property::Black].
+    White(Option<Point>),
+    /// KO[]
+    Ko,
+    /// MN[]
+    MoveNumber(i64),
+
+    // Setup properties.
+    /// AB[]
+    AddBlack(Vec<Point>),
+    /// AE[]
+    AddEmpty(Vec<Point>),
+    /// AW[]
+    AddWhite(Vec<Point>),
+    /// PL[]
+    PlayerToPlay(Stone),
+
+    // Node annotation properties.
+    /// C[]
+    Comment(String),
+    /// DM[]
+    EvenPosition(Double),
+    /// GB[]
+    GoodForBlack(Double),
+    /// GW[]
+    GoodForWhite(Double),
+    /// HO[]
+    Hotspot(Double),
+    /// N[]
+    NodeName(String),
+    /// UC[]
+    UnclearPosition(Double),
+    /// V[]
+    Value(f64),
+
+    // Move annotation properties.
+    /// BM[]
+    BadMove(Double),
+    /// DO[]
+    DoubtfulMove,
+    /// IT[]
+    InterestingMove,
+    /// TE[]
+    Tesuji(Double),
+
+    // Markup properties.
+    /// AR[], arrows from the first point to the second.
+    Arrow(Vec<(Point, Point)>),
+    /// CR[]
+    Circle(Vec<Point>),
+    /// DD[], an empty list clears the dimmed points.
+    Dim(Vec<Point>),
+    /// LB[]
+    Label(Vec<(Point, String)>),
+    /// LN[]
+    Line(Vec<(Point, Point)>),
+    /// MA[]
+    Mark(Vec<Point>),
+    /// SL[]
+    Selected(Vec<Point>),
+    /// SQ[]
+    Square(Vec<Point>),
+    /// TR[]
+    Triangle(Vec<Point>),
+    /// TB[], black's territory. An empty list marks none.
+    TerritoryBlack(Vec<Point>),
+    /// TW[], white's territory. An empty list marks none.
+    TerritoryWhite(Vec<Point>),
+
+    // Root properties.
+    /// AP[], the name and version of the application.
+    Application(String, String),
+    /// CA[]
+    Charset(String),
+    /// FF[]
+    FileFormat(u32),
+    /// GM[], 1 for go.
+    Game(u32),
+    /// ST[]
+    Style(u32),
+    /// SZ[], (width, height).
+    Size(usize, usize),
+
+    // Game info properties.
+    /// AN[]
+    Annotator(String),
+    /// BR[]
+    BlackRank(String),
+    /// BT[]
+    BlackTeam(String),
+    /// CP[]
+    Copyright(String),
+    /// DT[]
+    Date(String),
+    /// EV[]
+    Event(String),
+    /// GC[]
+    GameComment(String),
+    /// GN[]
+    GameName(String),
+    /// HA[]
+    Handicap(i64),
+    /// KM[]
+    Komi(f64),
+    /// ON[]
+    Opening(String),
+    /// OT[]
+    Overtime(String),
+    /// PB[]
+    PlayerBlack(String),
+    /// PC[]
+    Place(String),
+    /// PW[]
+    PlayerWhite(String),
+    /// RE[]
+    Result(String),
+    /// RO[]
+    Round(String),
+    /// RU[]
+    Rules(String),
+    /// SO[]
+    Source(String),
+    /// TM[], in seconds.
+    TimeLimit(f64),
+    /// US[]
+    User(String),
+    /// WR[]
+    WhiteRank(String),
+    /// WT[]
+    WhiteTeam(String),
+
+    // Timing properties.
+    /// BL[], in seconds.
+    BlackTimeLeft(f64),
+    /// WL[], in seconds.
+    WhiteTimeLeft(f64),
+    /// OB[]
+    BlackOvertimeStones(i64),
+    /// OW[]
+    WhiteOvertimeStones(i64),
+
+    // Miscellaneous properties.
+    /// FG[], [None] for the empty value that just starts a new figure.
+    Figure(Option<(i64, String)>),
+    /// PM[]
+    PrintMoveMode(i64),
+    /// VW[], an empty list shows the whole board again.
+    View(Vec<Point>),
+
+    Unknown(SgfProp),
+}
+impl Property {
+    /// Parse the value of a property with a known id.
+    /// Properties with an unknown id are kept as [Property::Unknown].
+    pub fn from_prop(prop: &SgfProp) -> ParseResult<Self> {
+        let value = || single(prop);
+
+        let property = match prop.id.as_str() {
+            "B" => Self::Black(parse_move(value()?)?),
+            "W" => Self::White(parse_move(value()?)?),
+            "KO" => none(prop, Self::Ko)?,
+            "MN" => Self::MoveNumber(value()?.parse()?),
+
+            "AB" => Self::AddBlack(parse_points(prop, false)?),
+            "AE" => Self::AddEmpty(parse_points(prop, false)?),
+            "AW" => Self::AddWhite(parse_points(prop, false)?),
+            "PL" => Self::PlayerToPlay(parse_color(value()?)?),
+
+            "C" => Self::Comment(unescape(value()?)),
+            "DM" => Self::EvenPosition(parse_double(value()?)?),
+            "GB" => Self::GoodForBlack(parse_double(value()?)?),
+            "GW" => Self::GoodForWhite(parse_double(value()?)?),
+            "HO" => Self::Hotspot(parse_double(value()?)?),
+            "N" => Self::NodeName(simple_text(value()?)),
+            "UC" => Self::UnclearPosition(parse_double(value()?)?),
+            "V" => Self::Value(value()?.parse()?),
+
+            "BM" => Self::BadMove(parse_double(value()?)?),
+            "DO" => none(prop, Self::DoubtfulMove)?,
+            "IT" => none(prop, Self::InterestingMove)?,
+            "TE" => Self::Tesuji(parse_double(value()?)?),
+
+            "AR" => Self::Arrow(parse_point_pairs(prop)?),
+            "CR" => Self::Circle(parse_points(prop, false)?),
+            "DD" => Self::Dim(parse_points(prop, true)?),
+            "LB" => Self::Label(
+                prop.values
+                    .iter()
+                    .map(|v| {
+                        let (p, text) = parse_composed(v)?;
+
+                        Ok((parse_coords(p)?, simple_text(text)))
+                    })
+                    .collect::<ParseResult<_>>()?,
+            ),
+            "LN" => Self::Line(parse_point_pairs(prop)?),
+            "MA" => Self::Mark(parse_points(prop, false)?),
+            "SL" => Self::Selected(parse_points(prop, false)?),
+            "SQ" => Self::Square(parse_points(prop, false)?),
+            "TR" => Self::Triangle(parse_points(prop, false)?),
+            "TB" => Self::TerritoryBlack(parse_points(prop, true)?),
+            "TW" => Self::TerritoryWhite(parse_points(prop, true)?),
+
+            "AP" => {
+                let (name, version) = parse_composed(value()?)?;
+
+                Self::Application(simple_text(name), simple_text(version))
+            }
+            "CA" => Self::Charset(simple_text(value()?)),
+            "FF" => Self::FileFormat(value()?.parse()?),
+            "GM" => Self::Game(value()?.parse()?),
+            "ST" => Self::Style(value()?.parse()?),
+            "SZ" => {
+                let (w, h) = parse_board_size(value()?)?;
+
+                Self::Size(w, h)
+            }
+
+            "AN" => Self::Annotator(simple_text(value()?)),
+            "BR" => Self::BlackRank(simple_text(value()?)),
+            "BT" => Self::BlackTeam(simple_text(value()?)),
+            "CP" => Self::Copyright(simple_text(value()?)),
+            "DT" => Self::Date(simple_text(value()?)),
+            "EV" => Self::Event(simple_text(value()?)),
+            "GC" => Self::GameComment(unescape(value()?)),
+            "GN" => Self::GameName(simple_text(value()?)),
+            "HA" => Self::Handicap(value()?.parse()?),
+            "KM" => Self::Komi(value()?.parse()?),
+            "ON" => Self::Opening(simple_text(value()?)),
+            "OT" => Self::Overtime(simple_text(value()?)),
+            "PB" => Self::PlayerBlack(simple_text(value()?)),
+            "PC" => Self::Place(simple_text(value()?)),
+            "PW" => Self::PlayerWhite(simple_text(value()?)),
+            "RE" => Self::Result(simple_text(value()?)),
+            "RO" => Self::Round(simple_text(value()?)),
+            "RU" => Self::Rules(simple_text(value()?)),
+            "SO" => Self::Source(simple_text(value()?)),
+            "TM" => Self::TimeLimit(value()?.parse()?),
+            "US" => Self::User(simple_text(value()?)),
+            "WR" => Self::WhiteRank(simple_text(value()?)),
+            "WT" => Self::WhiteTeam(simple_text(value()?)),
+
+            "BL" => Self::BlackTimeLeft(value()?.parse()?),
+            "WL" => Self::WhiteTimeLeft(value()?.parse()?),
+            "OB" => Self::BlackOvertimeStones(value()?.parse()?),
+            "OW" => Self::WhiteOvertimeStones(value()?.parse()?),
+
+            "FG" => match value()? {
+                "" => Self::Figure(None),
+                v => {
+                    let (flags, name) = parse_composed(v)?;
+
+                    Self::Figure(Some((flags.parse()?, simple_text(name))))
+                }
+            },
+            "PM" => Self::PrintMoveMode(value()?.parse()?),
+            "VW" => Self::View(parse_points(prop, true)?),
+
+            _ => Self::Unknown(prop.clone()),
+        };
+
+        Ok(property)
+    }
+
+    /// The property id, such as `AB`.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Black(_) => "B",
+            Self::White(_) => "W",
+            Self::Ko => "KO",
+            Self::MoveNumber(_) => "MN",
+
+            Self::AddBlack(_) => "AB",
+            Self::AddEmpty(_) => "AE",
+            Self::AddWhite(_) => "AW",
+            Self::PlayerToPlay(_) => "PL",
+
+            Self::Comment(_) => "C",
+            Self::EvenPosition(_) => "DM",
+            Self::GoodForBlack(_) => "GB",
+            Self::GoodForWhite(_) => "GW",
+            Self::Hotspot(_) => "HO",
+            Self::NodeName(_) => "N",
+            Self::UnclearPosition(_) => "UC",
+            Self::Value(_) => "V",
+
+            Self::BadMove(_) => "BM",
+            Self::DoubtfulMove => "DO",
+            Self::InterestingMove => "IT",
+            Self::Tesuji(_) => "TE",
+
+            Self::Arrow(_) => "AR",
+            Self::Circle(_) => "CR",
+            Self::Dim(_) => "DD",
+            Self::Label(_) => "LB",
+            Self::Line(_) => "LN",
+            Self::Mark(_) => "MA",
+            Self::Selected(_) => "SL",
+            Self::Square(_) => "SQ",
+            Self::Triangle(_) => "TR",
+            Self::TerritoryBlack(_) => "TB",
+            Self::TerritoryWhite(_) => "TW",
+
+            Self::Application(..) => "AP",
+            Self::Charset(_) => "CA",
+            Self::FileFormat(_) => "FF",
+            Self::Game(_) => "GM",
+            Self::Style(_) => "ST",
+            Self::Size(..) => "SZ",
+
+            Self::Annotator(_) => "AN",
+            Self::BlackRank(_) => "BR",
+            Self::BlackTeam(_) => "BT",
+            Self::Copyright(_) => "CP",
+            Self::Date(_) => "DT",
+            Self::Event(_) => "EV",
+            Self::GameComment(_) => "GC",
+            Self::GameName(_) => "GN",
+            Self::Handicap(_) => "HA",
+            Self::Komi(_) => "KM",
+            Self::Opening(_) => "ON",
+            Self::Overtime(_) => "OT",
+            Self::PlayerBlack(_) => "PB",
+            Self::Place(_) => "PC",
+            Self::PlayerWhite(_) => "PW",
+            Self::Result(_) => "RE",
+            Self::Round(_) => "RO",
+            Self::Rules(_) => "RU",
+            Self::Source(_) => "SO",
+            Self::TimeLimit(_) => "TM",
+            Self::User(_) => "US",
+            Self::WhiteRank(_) => "WR",
+            Self::WhiteTeam(_) => "WT",
+
+            Self::BlackTimeLeft(_) => "BL",
+            Self::WhiteTimeLeft(_) => "WL",
+            Self::BlackOvertimeStones(_) => "OB",
+            Self::WhiteOvertimeStones(_) => "OW",
+
+            Self::Figure(_) => "FG",
+            Self::PrintMoveMode(_) => "PM",
+            Self::View(_) => "VW",
+
+            Self::Unknown(p) => &p.id,
+        }
+    }
+
+    /// Write the property back as an [SgfProp]. The reverse of [Property::from_prop].
+    pub fn to_prop(&self) -> SgfProp {
+        let values: Vec<String> = match self {
+            Self::Black(m) | Self::White(m) => vec![m.map(format_coords).unwrap_or_default()],
+
+            Self::Ko | Self::DoubtfulMove | Self::InterestingMove => vec![String::new()],
+
+            Self::MoveNumber(n)
+            | Self::Handicap(n)
+            | Self::BlackOvertimeStones(n)
+            | Self::WhiteOvertimeStones(n)
+            | Self::PrintMoveMode(n) => vec![n.to_string()],
+
+            Self::FileFormat(n) | Self::Game(n) | Self::Style(n) => vec![n.to_string()],
+
+            Self::Value(r)
+            | Self::Komi(r)
+            | Self::TimeLimit(r)
+            | Self::BlackTimeLeft(r)
+            | Self::WhiteTimeLeft(r) => vec![r.to_string()],
+
+            Self::AddBlack(points)
+            | Self::AddEmpty(points)
+            | Self::AddWhite(points)
+            | Self::Circle(points)
+            | Self::Dim(points)
+            | Self::Mark(points)
+            | Self::Selected(points)
+            | Self::Square(points)
+            | Self::Triangle(points)
+            | Self::TerritoryBlack(points)
+            | Self::TerritoryWhite(points)
+            | Self::View(points) => compress_points(points),
+
+            Self::PlayerToPlay(color) => vec![match color {
+                Stone::White => String::from("W"),
+                _ => String::from("B"),
+            }],
+
+            Self::EvenPosition(d)
+            | Self::GoodForBlack(d)
+            | Self::GoodForWhite(d)
+            | Self::Hotspot(d)
+            | Self::UnclearPosition(d)
+            | Self::BadMove(d)
+            | Self::Tesuji(d) => vec![match d {
+                Double::Normal => String::from("1"),
+                Double::Emphasized => String::from("2"),
+            }],
+
+            Self::Arrow(pairs) | Self::Line(pairs) => pairs
+                .iter()
+                .map(|&(a, b)| format!("{}:{}", format_coords(a), format_coords(b)))
+                .collect(),
+
+            Self::Label(labels) => labels
+                .iter()
+                .map(|(p, text)| format_composed(&format_coords(*p), text))
+                .collect(),
+
+            Self::Application(name, version) => vec![format_composed(name, version)],
+
+            Self::Size(w, h) => vec![format_board_size((*w, *h))],
+
+            Self::Comment(s)
+            | Self::NodeName(s)
+            | Self::Charset(s)
+            | Self::Annotator(s)
+            | Self::BlackRank(s)
+            | Self::BlackTeam(s)
+            | Self::Copyright(s)
+            | Self::Date(s)
+            | Self::Event(s)
+            | Self::GameComment(s)
+            | Self::GameName(s)
+            | Self::Opening(s)
+            | Self::Overtime(s)
+            | Self::PlayerBlack(s)
+            | Self::Place(s)
+            | Self::PlayerWhite(s)
+            | Self::Result(s)
+            | Self::Round(s)
+            | Self::Rules(s)
+            | Self::Source(s)
+            | Self::User(s)
+            | Self::WhiteRank(s)
+            | Self::WhiteTeam(s) => vec![escape(s)],
+
+            Self::Figure(None) => vec![String::new()],
+            Self::Figure(Some((flags, name))) => vec![format_composed(&flags.to_string(), name)],
+
+            Self::Unknown(p) => return p.clone(),
+        };
+
+        SgfProp {
+            id: self.id().into(),
+            values,
+        }
+    }
+}
+
+/// The only value of a property that takes a single value.
+fn single(prop: &SgfProp) -> ParseResult<&str> {
+    match prop.values.as_slice() {
+        [v] => Ok(v),
+        _ => Err(ParseError::WrongValueCount(prop.id.clone())),
+    }
+}
+
+/// A property with no value, written as `XX[]`.
+fn none(prop: &SgfProp, property: Property) -> ParseResult<Property> {
+    match single(prop)? {
+        "" => Ok(property),
+        v => Err(ParseError::InvalidValue(String::from(v))),
+    }
+}
+
+/// Unescape a SimpleText value and turn its line breaks into spaces.
+fn simple_text(v: &str) -> String {
+    unescape(v)
+        .replace("\r\n", " ")
+        .replace("\n\r", " ")
+        .replace(['\n', '\r'], " ")
+}
+
+fn parse_move(v: &str) -> ParseResult<Option<Point>> {
+    if v.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(parse_coords(v)?))
+}
+
+fn parse_color(v: &str) -> ParseResult<Stone> {
+    match v {
+        "B" => Ok(Stone::Black),
+        "W" => Ok(Stone::White),
+        _ => Err(ParseError::InvalidValue(String::from(v))),
+    }
+}
+
+fn parse_double(v: &str) -> ParseResult<Double> {
+    match v {
+        "1" => Ok(Double::Normal),
+        "2" => Ok(Double::Emphasized),
+        _ => Err(ParseError::InvalidValue(String::from(v))),
+    }
+}
+
+/// If `allow_empty`, a single empty value is an empty list.
+fn parse_points(prop: &SgfProp, allow_empty: bool) -> ParseResult<Vec<Point>> {
+    let points = parse_point_list(&prop.values)?;
+
+    if points.is_empty() && !allow_empty {
+        return Err(ParseError::CoordinateParseError);
+    }
+
+    return Ok(points);
+}
+
+fn parse_point_pairs(prop: &SgfProp) -> ParseResult<Vec<(Point, Point)>> {
+    prop.values
+        .iter()
+        .map(|v| {
+            let (a, b) = parse_composed(v)?;
+
+            Ok((parse_coords(a)?, parse_coords(b)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(id: &str, values: Vec<&str>) -> ParseResult<Property> {
+        Property::from_prop(&SgfProp::new_many(id, values))
+    }
+
+    #[test]
+    fn typed_values() {
+        assert_eq!(parse("B", vec!["cd"]), Ok(Property::Black(Some((2, 3)))));
+        assert_eq!(parse("W", vec![""]), Ok(Property::White(None)));
+        assert_eq!(parse("KM", vec!["6.5"]), Ok(Property::Komi(6.5)));
+        assert_eq!(parse("SZ", vec!["9:13"]), Ok(Property::Size(9, 13)));
+        assert_eq!(
+            parse("PL", vec!["W"]),
+            Ok(Property::PlayerToPlay(Stone::White))
+        );
+        assert_eq!(
+            parse("TE", vec!["2"]),
+            Ok(Property::Tesuji(Double::Emphasized))
+        );
+        assert_eq!(parse("IT", vec![""]), Ok(Property::InterestingMove));
+        assert_eq!(
+            parse("AP", vec!["mbaduk:0.1"]),
+            Ok(Property::Application("mbaduk".into(), "0.1".into()))
+        );
+        assert_eq!(
+            parse("AP", vec![r"a\:b:1.0"]),
+            Ok(Property::Application("a:b".into(), "1.0".into()))
+        );
+        assert_eq!(
+            parse("LB", vec!["aa:one", "bb:two\nlines"]),
+            Ok(Property::Label(vec![
+                ((0, 0), "one".into()),
+                ((1, 1), "two lines".into())
+            ]))
+        );
+        assert_eq!(
+            parse("C", vec!["two\nlines"]),
+            Ok(Property::Comment("two\nlines".into()))
+        );
+        assert_eq!(
+            parse("FG", vec!["257:Figure 1"]),
+            Ok(Property::Figure(Some((257, "Figure 1".into()))))
+        );
+    }
+
+    #[test]
+    fn point_lists() {
+        assert_eq!(
+            parse("AB", vec!["aa:bb", "dd"]),
+            Ok(Property::AddBlack(vec![
+                (0, 0),
+                (1, 0),
+                (0, 1),
+                (1, 1),
+                (3, 3)
+            ]))
+        );
+        assert_eq!(parse("VW", vec![""]), Ok(Property::View(Vec::new())));
+        assert_eq!(
+            parse("TB", vec!["aa:ab"]),
+            Ok(Property::TerritoryBlack(vec![(0, 0), (0, 1)]))
+        );
+        assert_eq!(
+            parse("TW", vec![""]),
+            Ok(Property::TerritoryWhite(Vec::new()))
+        );
+        assert_eq!(parse("TR", vec![""]), Err(ParseError::CoordinateParseError));
+        assert_eq!(
+            parse("AR", vec!["aa:cc"]),
+            Ok(Property::Arrow(vec![((0, 0), (2, 2))]))
+        );
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert_eq!(
+            parse("B", vec!["aa", "bb"]),
+            Err(ParseError::WrongValueCount("B".into()))
+        );
+        assert_eq!(
+            parse("KO", vec!["x"]),
+            Err(ParseError::InvalidValue("x".into()))
+        );
+        assert_eq!(
+            parse("GB", vec!["3"]),
+            Err(ParseError::InvalidValue("3".into()))
+        );
+        assert!(matches!(
+            parse("KM", vec!["six"]),
+            Err(ParseError::FloatParse(_))
+        ));
+        assert!(matches!(
+            parse("HA", vec!["2.5"]),
+            Err(ParseError::IntParse(_))
+        ));
+    }
+
+    #[test]
+    fn round_trip() {
+        let props = [
+            SgfProp::new("B", "cd"),
+            SgfProp::new("KO", ""),
+            SgfProp::new_many("AW", vec!["aa", "bb"]),
+            SgfProp::new_many("AB", vec!["aa:bc", "dd"]),
+            SgfProp::new("DD", ""),
+            SgfProp::new_many("TW", vec!["aa:ba", "dd"]),
+            SgfProp::new("GW", "1"),
+            SgfProp::new_many("LN", vec!["aa:bb", "cc:dd"]),
+            SgfProp::new("SZ", "19"),
+            SgfProp::new("KM", "0.5"),
+            SgfProp::new("AP", "mbaduk:0.1"),
+            SgfProp::new("AP", r"a\:b:1\]0"),
+            SgfProp::new_many("LB", vec![r"aa:x\:y", "bb:z"]),
+            SgfProp::new("FG", r"0:a\:b"),
+            SgfProp::new("FG", ""),
+            SgfProp::new("XY", "unknown"),
+        ];
+
+        for p in props {
+            let property = Property::from_prop(&p).unwrap();
+
+            assert_eq!(property.id(), p.id);
+            assert_eq!(property.to_prop(), p);
+        }
+
+        assert!(matches!(
+            parse("XY", vec!["unknown"]),
+            Ok(Property::Unknown(_))
+        ));
+    }
+}
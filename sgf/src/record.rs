@@ -0,0 +1,302 @@
+use mb_goban::{Clock, Game, GameResult, Stone, TimeSource, WinReason};
+
+use crate::tree::{SgfNode, SgfProp, SgfTree};
+use crate::util::{compress_points, format_board_size, format_coords};
+
+/// Information about a game that a [Game] doesn't keep track of, for [record_game].
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct GameInfo {
+    /// KM[]
+    pub komi: Option<f64>,
+    /// RU[], such as `Japanese` or `Chinese`.
+    pub rules: Option<String>,
+    /// RE[], such as `W+3.5`. Only needed when the result is decided by counting,
+    /// since wins by resignation, time or captures are recorded from the [Game].
+    pub result: Option<String>,
+    /// PB[], instead of the black players of the game's [TurnOrder](mb_goban::TurnOrder).
+    pub black: Option<String>,
+    /// PW[], instead of the white players of the game's [TurnOrder](mb_goban::TurnOrder).
+    pub white: Option<String>,
+    /// DT[], such as `2024-03-01`.
+    pub date: Option<String>,
+    /// C[] comments, each with the number of moves before it: 0 for the root node,
+    /// 1 for the node of the first move and so on.
+    pub comments: Vec<(usize, String)>,
+}
+
+/// Record a [Game] as an [SgfTree] with a single line of play.
+/// Returns [None] if the board is wider or taller than 52, the most sgf coordinates can hold.
+///
+/// The root node has the game information, and the stones the game started with as AB[] and AW[].
+/// For team games, the players of each color are listed in their turn order
+/// in PB[] and PW[], separated by commas, and the node of each move has the player
+/// who made it in the private property PN[].
+pub fn record_game(game: &Game, info: &GameInfo) -> Option<SgfTree> {
+    let (w, h) = game.board().size();
+
+    if w > 52 || h > 52 {
+        return None;
+    }
+
+    let mut root = SgfNode::new([
+        SgfProp::new("GM", "1"),
+        SgfProp::new("FF", "4"),
+        SgfProp::new("CA", "UTF-8"),
+        SgfProp::new("SZ", &format_board_size(game.board().size())),
+    ]);
+
+    if let Some(komi) = info.komi {
+        root.props.push(SgfProp::new("KM", &komi.to_string()));
+    }
+    if let Some(rules) = &info.rules {
+        root.props.push(SgfProp::new_text("RU", rules));
+    }
+    if let Some(result) = info
+        .result
+        .clone()
+        .or_else(|| format_result(game.result()?))
+    {
+        root.props.push(SgfProp::new_text("RE", &result));
+    }
+
+    for (id, color, name) in [
+        ("PB", Stone::Black, &info.black),
+        ("PW", Stone::White, &info.white),
+    ] {
+        let team = game.turn_order().map(|o| o.team(color).join(", "));
+
+        match name.clone().or(team) {
+            Some(name) if !name.is_empty() => root.props.push(SgfProp::new_text(id, &name)),
+            _ => {}
+        }
+    }
+
+    if let Some(date) = &info.date {
+        root.props.push(SgfProp::new_text("DT", date));
+    }
+
+    root.props.extend(setup_props(game));
+
+    let mut nodes = vec![root];
+
+    for m in game.moves() {
+        let id = match m.color {
+            Stone::White => "W",
+            _ => "B",
+        };
+
+        let value = m.point.map(format_coords).unwrap_or_default();
+        let mut node = SgfNode::new([SgfProp::new(id, &value)]);
+
+        if let Some(player) = &m.player {
+            node.props.push(SgfProp::new_text("PN", player));
+        }
+
+        nodes.push(node);
+    }
+
+    for (n, node) in nodes.iter_mut().enumerate() {
+        let comments: Vec<&str> = info
+            .comments
+            .iter()
+            .filter(|c| c.0 == n)
+            .map(|c| c.1.as_str())
+            .collect();
+
+        if !comments.is_empty() {
+            node.props
+                .push(SgfProp::new_text("C", &comments.join("\n\n")));
+        }
+    }
+
+    return Some(SgfTree::new(nodes, []));
+}
+
+/// RE[] for a game won by resignation, time or captures.
+/// [None] for games decided by counting, since the score isn't known.
+fn format_result(result: GameResult) -> Option<String> {
+    let GameResult::Win { winner, reason } = result else {
+        return None;
+    };
+
+    let winner = match winner {
+        Stone::White => "W",
+        _ => "B",
+    };
+
+    let reason = match reason {
+        WinReason::Resignation => "R",
+        WinReason::Time => "T",
+        WinReason::Captures => "",
+    };
+
+    Some(format!("{winner}+{reason}"))
+}
+
+/// AB[] and AW[] for the stones on the board before the first move of `game`.
+fn setup_props(game: &Game) -> Vec<SgfProp> {
+    let mut board = game.board().clone();
+    let start = board.history().count() - game.moves().len();
+
+    // Setup after the first move, from Game::setup, is undone along with the moves.
+    while board.history().count() > start {
+        board.undo().unwrap();
+    }
+
+    let (w, h) = board.size();
+    let mut props = Vec::new();
+
+    for (id, color) in [("AB", Stone::Black), ("AW", Stone::White)] {
+        let points: Vec<(usize, usize)> = (0..h)
+            .flat_map(|y| (0..w).map(move |x| (x, y)))
+            .filter(|&(x, y)| board.get(x, y) == Ok(color))
+            .collect();
+
+        if !points.is_empty() {
+            let values = compress_points(&points);
+
+            props.push(SgfProp {
+                id: id.into(),
+                values,
+            });
+        }
+    }
+
+    return props;
+}
+
+/// The BL[]/OB[] or WL[]/OW[] properties for the time `color` has left on `clock`.
+/// The overtime property is only included once the main time has run out
+/// of a byo-yomi or Canadian time control.
+pub fn clock_props<T: TimeSource>(clock: &Clock<T>, color: Stone) -> Vec<SgfProp> {
+    let (time, overtime) = match color {
+        Stone::White => ("WL", "OW"),
+        _ => ("BL", "OB"),
+    };
+
+    let secs = format!("{:.3}", clock.time_left(color).as_secs_f64());
+    let secs = secs.trim_end_matches('0').trim_end_matches('.');
+
+    let mut props = vec![SgfProp::new(time, secs)];
+
+    if let Some(n) = clock.overtime_left(color) {
+        props.push(SgfProp::new(overtime, &n.to_string()));
+    }
+
+    return props;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mb_goban::{Board, ManualTime, Rules, TimeControl, TurnOrder, Variant};
+    use std::time::Duration;
+
+    #[test]
+    fn rengo() -> mb_goban::Result<()> {
+        let mut game = Game::new(Board::empty(9, 9), Rules::JAPANESE, Variant::Standard);
+        game.set_turn_order(TurnOrder::new(&["Ann", "Cat", "Eve"], &["Bob", "Dan"]));
+
+        game.play(2, 2)?;
+        game.play(6, 6)?;
+        game.pass()?;
+
+        assert_eq!(
+            record_game(&game, &GameInfo::default())
+                .unwrap()
+                .to_string(),
+            "(;GM[1]FF[4]CA[UTF-8]SZ[9]PB[Ann, Cat, Eve]PW[Bob, Dan]\
+             ;B[cc]PN[Ann];W[gg]PN[Bob];B[]PN[Cat])"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn setup_after_first_move() -> mb_goban::Result<()> {
+        let mut game = Game::new(Board::empty(9, 9), Rules::JAPANESE, Variant::Standard);
+
+        game.play(2, 2)?;
+        game.setup(&[((4, 4), Stone::White)])?;
+        game.play(6, 6)?;
+
+        assert_eq!(
+            record_game(&game, &GameInfo::default())
+                .unwrap()
+                .to_string(),
+            "(;GM[1]FF[4]CA[UTF-8]SZ[9];B[cc];W[gg])"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn board_too_big() {
+        let game = Game::new(Board::empty(53, 19), Rules::JAPANESE, Variant::Standard);
+
+        assert_eq!(record_game(&game, &GameInfo::default()), None);
+    }
+
+    #[test]
+    fn game_info() -> mb_goban::Result<()> {
+        let mut board = Board::empty(9, 9);
+
+        for (x, y) in [(2, 2), (3, 2), (6, 6)] {
+            board.place(x, y, Stone::Black, &Rules::JAPANESE)?;
+        }
+
+        let mut game = Game::new(board, Rules::JAPANESE, Variant::Standard);
+        game.play(4, 4)?;
+        // White resigns.
+        game.resign()?;
+
+        let info = GameInfo {
+            komi: Some(0.5),
+            rules: Some(String::from("Japanese")),
+            black: Some(String::from("Ann")),
+            date: Some(String::from("2024-03-01")),
+            comments: vec![
+                (0, String::from("Handicap game")),
+                (1, String::from("Tengen")),
+            ],
+            ..GameInfo::default()
+        };
+
+        assert_eq!(
+            record_game(&game, &info).unwrap().to_string(),
+            "(;GM[1]FF[4]CA[UTF-8]SZ[9]KM[0.5]RU[Japanese]RE[B+R]PB[Ann]DT[2024-03-01]\
+             AB[cc:dc][gg]C[Handicap game];B[ee]C[Tengen])"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_left() {
+        let time = ManualTime::new();
+        let mut clock = Clock::new(
+            TimeControl::ByoYomi {
+                main: Duration::from_secs(60),
+                period: Duration::from_secs(30),
+                periods: 5,
+            },
+            time.clone(),
+        );
+
+        clock.start(Stone::Black);
+        time.advance(Duration::from_millis(10_500));
+        clock.press();
+
+        assert_eq!(
+            clock_props(&clock, Stone::Black),
+            [SgfProp::new("BL", "49.5")]
+        );
+
+        time.advance(Duration::from_secs(70));
+
+        assert_eq!(
+            clock_props(&clock, Stone::White),
+            [SgfProp::new("WL", "20"), SgfProp::new("OW", "5")]
+        );
+    }
+}
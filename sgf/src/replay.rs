@@ -0,0 +1,293 @@
+use mb_goban::{Board, Rules, Stone};
+
+use crate::tree::{SgfNode, SgfTree};
+use crate::util::{parse_board_size, parse_move, parse_point_list};
+use crate::ParseError;
+
+/// Why a node couldn't be replayed.
+#[derive(Clone, PartialEq, Debug)]
+pub enum NodeError {
+    /// The path doesn't lead to a node of the tree.
+    Missing,
+    /// A property of the node has an invalid value.
+    Parse(ParseError),
+    /// A move or setup stone of the node couldn't be played.
+    Illegal(mb_goban::Error),
+}
+
+/// A [NodeError] and the path of the node that caused it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReplayError {
+    pub path: Vec<usize>,
+    pub error: NodeError,
+}
+
+/// Plays the setup stones and moves of sgf nodes on a [Board], one node at a time.
+///
+/// The board starts out 19x19, and SZ[] in the root node changes its size.
+#[derive(Clone)]
+pub struct Replay {
+    board: Board,
+    rules: Rules,
+    moves: Vec<(Stone, Option<(usize, usize)>)>,
+}
+impl Replay {
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            board: Board::default(),
+            rules,
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Every move played so far, with [None] for passes.
+    pub fn moves(&self) -> &[(Stone, Option<(usize, usize)>)] {
+        &self.moves
+    }
+
+    /// Apply the AB[], AW[] and AE[] setup properties of `node`, then its B[] or W[] move.
+    /// If an error is returned, the node may have been partly applied.
+    pub fn apply(&mut self, node: &SgfNode) -> Result<(), NodeError> {
+        for prop in &node.props {
+            let value = prop.values.first().map(String::as_str).unwrap_or_default();
+
+            if prop.id == "SZ" {
+                // The size can't change once there are stones on the board.
+                let (w, h) = self.board.size();
+
+                if !self.moves.is_empty() || self.board != Board::empty(w, h) {
+                    return Err(NodeError::Parse(ParseError::InvalidValue(value.into())));
+                }
+
+                let (w, h) = parse_board_size(value).map_err(NodeError::Parse)?;
+
+                // Coordinates only go up to 52.
+                if w == 0 || h == 0 || w > 52 || h > 52 {
+                    return Err(NodeError::Parse(ParseError::InvalidValue(value.into())));
+                }
+
+                self.board = Board::empty(w, h);
+            }
+        }
+
+        let mut setup = Vec::new();
+
+        for prop in &node.props {
+            let color = match prop.id.as_str() {
+                "AB" => Stone::Black,
+                "AW" => Stone::White,
+                "AE" => Stone::Empty,
+                _ => continue,
+            };
+
+            for p in parse_point_list(&prop.values).map_err(NodeError::Parse)? {
+                setup.push((p, color));
+            }
+        }
+
+        // Setup stones never capture, so the order of the properties doesn't matter.
+        if !setup.is_empty() {
+            self.board.setup(&setup).map_err(NodeError::Illegal)?;
+        }
+
+        for prop in &node.props {
+            let color = match prop.id.as_str() {
+                "B" => Stone::Black,
+                "W" => Stone::White,
+                _ => continue,
+            };
+
+            let value = match prop.values.as_slice() {
+                [v] => v,
+                _ => {
+                    return Err(NodeError::Parse(ParseError::WrongValueCount(
+                        prop.id.clone(),
+                    )))
+                }
+            };
+
+            let point = parse_move(value, self.board.size()).map_err(NodeError::Parse)?;
+
+            match point {
+                Some((x, y)) => {
+                    self.board
+                        .play(x, y, color, &self.rules)
+                        .map_err(NodeError::Illegal)?;
+                }
+                None => self.board.pass(),
+            }
+
+            self.moves.push((color, point));
+        }
+
+        Ok(())
+    }
+}
+
+/// The nodes from the root of `tree` to the node at the [path](crate::Cursor) `path`.
+/// [None] if the path doesn't lead to a node.
+pub fn nodes_on_path<'a>(tree: &'a SgfTree, path: &[usize]) -> Option<Vec<&'a SgfNode>> {
+    let mut cursor = tree.cursor()?;
+    let mut nodes = vec![cursor.node()];
+
+    for &i in path {
+        nodes.push(cursor.child(i)?);
+    }
+
+    return Some(nodes);
+}
+
+/// The [Board] after the node at `path` (see [nodes_on_path]), replayed from the root.
+pub fn replay(tree: &SgfTree, path: &[usize], rules: &Rules) -> Result<Board, ReplayError> {
+    let nodes = nodes_on_path(tree, path).ok_or(ReplayError {
+        path: path.to_vec(),
+        error: NodeError::Missing,
+    })?;
+
+    let mut replay = Replay::new(*rules);
+
+    for (depth, node) in nodes.into_iter().enumerate() {
+        replay.apply(node).map_err(|error| ReplayError {
+            path: path[..depth].to_vec(),
+            error,
+        })?;
+    }
+
+    return Ok(replay.into_board());
+}
+
+/// The [Board] at the end of the main line of `tree`.
+pub fn replay_main_line(tree: &SgfTree, rules: &Rules) -> Result<Board, ReplayError> {
+    let length = tree.main_line().count();
+
+    replay(tree, &vec![0; length.saturating_sub(1)], rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, parse_lenient};
+    use mb_goban::IllegalMove;
+
+    const LENIENT: Rules = Rules {
+        suicide_allowed: true,
+        superko: false,
+    };
+
+    #[test]
+    fn setup_and_moves() -> Result<(), ReplayError> {
+        let tree =
+            parse("(;SZ[5]AB[aa:ba]AW[ca];W[ab];B[tt];AE[aa](;W[cc])(;W[dd];B[ee]))").unwrap();
+
+        let board = replay_main_line(&tree, &Rules::JAPANESE)?;
+
+        // AE[] removes black's stone at aa again.
+        assert_eq!(board.size(), (5, 5));
+        assert_eq!(board.get(0, 0), Ok(Stone::Empty));
+        assert_eq!(board.get(1, 0), Ok(Stone::Black));
+        assert_eq!(board.get(2, 0), Ok(Stone::White));
+        assert_eq!(board.get(2, 2), Ok(Stone::White));
+        assert_eq!(board.get(3, 3), Ok(Stone::Empty));
+
+        let board = replay(&tree, &[0, 0, 0, 1, 0], &Rules::JAPANESE)?;
+
+        assert_eq!(board.get(3, 3), Ok(Stone::White));
+        assert_eq!(board.get(4, 4), Ok(Stone::Black));
+        assert_eq!(board.get(2, 2), Ok(Stone::Empty));
+
+        let board = replay(&tree, &[], &Rules::JAPANESE)?;
+
+        assert_eq!(board.get(0, 1), Ok(Stone::Empty));
+
+        Ok(())
+    }
+
+    #[test]
+    fn setup_never_captures() -> Result<(), ReplayError> {
+        // + b +
+        // b w b
+        // + b +
+        let tree = parse("(;SZ[3]AW[bb]AB[ba][ab][cb][bc])").unwrap();
+
+        // Suicide isn't allowed, but neither the white stone nor the order of AB[] and AW[]
+        // matters for setup.
+        let board = replay_main_line(&tree, &Rules::JAPANESE)?;
+
+        assert_eq!(board.get(1, 1), Ok(Stone::White));
+        assert_eq!(board.get(1, 0), Ok(Stone::Black));
+
+        let reordered = parse("(;SZ[3]AB[ba][ab][cb][bc]AW[bb])").unwrap();
+
+        assert_eq!(
+            replay_main_line(&reordered, &Rules::JAPANESE)?.position_hash(),
+            board.position_hash()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn moves_and_passes() {
+        let tree = parse("(;SZ[9];B[cc];W[];B[tt])").unwrap();
+        let mut replay = Replay::new(LENIENT);
+
+        for n in &tree.nodes {
+            replay.apply(n).unwrap();
+        }
+
+        assert_eq!(
+            replay.moves(),
+            [
+                (Stone::Black, Some((2, 2))),
+                (Stone::White, None),
+                (Stone::Black, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let tree = parse("(;SZ[3];B[aa](;W[aa])(;W[bb];B[zz]))").unwrap();
+
+        assert_eq!(
+            replay(&tree, &[0, 0], &LENIENT).err(),
+            Some(ReplayError {
+                path: vec![0, 0],
+                error: NodeError::Illegal(mb_goban::Error::IllegalMove(IllegalMove::NonEmptySpace)),
+            })
+        );
+        assert_eq!(
+            replay(&tree, &[0, 1, 0], &LENIENT).err(),
+            Some(ReplayError {
+                path: vec![0, 1, 0],
+                error: NodeError::Illegal(mb_goban::Error::CoordinatesOutOfBounds),
+            })
+        );
+        assert_eq!(
+            replay(&tree, &[0, 2], &LENIENT).err(),
+            Some(ReplayError {
+                path: vec![0, 2],
+                error: NodeError::Missing,
+            })
+        );
+
+        // The strict parser rejects B[a] itself, so the tree comes from the lenient one.
+        let (collection, _) = parse_lenient("(;SZ[3];B[a];W[bb])").unwrap();
+
+        assert_eq!(
+            replay_main_line(&collection.trees[0], &LENIENT).err(),
+            Some(ReplayError {
+                path: vec![0],
+                error: NodeError::Parse(ParseError::CoordinateParseError),
+            })
+        );
+    }
+}
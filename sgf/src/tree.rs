@@ -1,10 +1,16 @@
 use std::fmt;
 
+use crate::property::Property;
+use crate::util::escape;
+use crate::ParseResult;
+
 /// Represents a single sgf property.
 /// This is the smallest unit of the sgf format.
 #[derive(Clone, Default, PartialEq, Debug)]
 pub struct SgfProp {
     pub id: String,
+    /// The values as written in the sgf file, escapes included.
+    /// [Property] and [unescape](crate::util::unescape) remove them.
     pub values: Vec<String>,
 }
 impl SgfProp {
@@ -15,6 +21,11 @@ impl SgfProp {
         }
     }
 
+    /// A property with a single text value, escaped with [escape].
+    pub fn new_text(id: &str, text: &str) -> Self {
+        Self::new(id, &escape(text))
+    }
+
     pub fn new_many(id: &str, values: Vec<&str>) -> Self {
         Self {
             id: id.into(),
@@ -28,7 +39,7 @@ impl fmt::Display for SgfProp {
         write!(f, "{}", self.id)?;
 
         for v in &self.values {
-            write!(f, "[{}]", v)?;
+            write!(f, "[{v}]")?;
         }
 
         Ok(())
@@ -47,6 +58,66 @@ impl SgfNode {
             props: props.into(),
         }
     }
+
+    /// The [Property] of each [SgfProp], in order.
+    pub fn properties(&self) -> ParseResult<Vec<Property>> {
+        self.props.iter().map(Property::from_prop).collect()
+    }
+
+    /// Translate properties that were removed in FF[4] to their FF[4] equivalents:
+    /// `L` labels become `LB` labels lettered a to z, then A to Z, then numbered from 53,
+    /// and `M` marks become `MA`. Values are added to any `LB` or `MA` already in the node.
+    ///
+    /// The other removed properties have no FF[4] equivalent and are kept as they are:
+    /// BS, CH, EL, EX, ID, LT, OM, OP, OV, RG, SC, SE, SI, TC and WS.
+    pub fn upgrade_ff3(&mut self) {
+        let mut labels = Vec::new();
+        let mut marks = Vec::new();
+
+        self.props.retain(|p| match p.id.as_str() {
+            "L" => {
+                labels.extend(p.values.iter().cloned());
+                false
+            }
+            "M" => {
+                marks.extend(p.values.iter().cloned());
+                false
+            }
+            _ => true,
+        });
+
+        let labels = labels
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| format!("{v}:{}", ff3_label(i)))
+            .collect();
+
+        self.add_values("LB", labels);
+        self.add_values("MA", marks);
+    }
+
+    fn add_values(&mut self, id: &str, values: Vec<String>) {
+        if values.is_empty() {
+            return;
+        }
+
+        match self.props.iter_mut().find(|p| p.id == id) {
+            Some(p) => p.values.extend(values),
+            None => self.props.push(SgfProp {
+                id: id.into(),
+                values,
+            }),
+        }
+    }
+}
+
+/// The text of the `i`th label of an FF[3] `L` property.
+fn ff3_label(i: usize) -> String {
+    match i {
+        0..26 => char::from(b'a' + i as u8).to_string(),
+        26..52 => char::from(b'A' + (i - 26) as u8).to_string(),
+        _ => (i + 1).to_string(),
+    }
 }
 
 impl fmt::Display for SgfNode {
@@ -74,6 +145,34 @@ impl SgfTree {
             children: children.into(),
         }
     }
+
+    /// [Upgrade](SgfNode::upgrade_ff3) every node of a game written in FF[1] to FF[3].
+    /// Games whose root has no FF[] are treated as FF[1], and newer games are left alone.
+    pub fn upgrade_ff3(&mut self) {
+        let version = self
+            .nodes
+            .first()
+            .and_then(|root| root.props.iter().find(|p| p.id == "FF"));
+
+        let old = match version {
+            None => true,
+            Some(p) => matches!(p.values.as_slice(), [v] if matches!(v.as_str(), "1" | "2" | "3")),
+        };
+
+        if old {
+            self.upgrade_nodes();
+        }
+    }
+
+    fn upgrade_nodes(&mut self) {
+        for n in &mut self.nodes {
+            n.upgrade_ff3();
+        }
+
+        for c in &mut self.children {
+            c.upgrade_nodes();
+        }
+    }
 }
 
 impl fmt::Display for SgfTree {
@@ -92,6 +191,29 @@ impl fmt::Display for SgfTree {
     }
 }
 
+/// The contents of an sgf file: one or more [SgfTree]s, usually one per game.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Collection {
+    pub trees: Vec<SgfTree>,
+}
+impl Collection {
+    pub fn new(trees: impl Into<Vec<SgfTree>>) -> Self {
+        Self {
+            trees: trees.into(),
+        }
+    }
+}
+
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for t in &self.trees {
+            writeln!(f, "{t}")?;
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,6 +228,44 @@ mod tests {
         assert_eq!(format!("{}", prop), String::from("AB[cd][ef]"));
     }
 
+    #[test]
+    fn upgrade_ff3() {
+        let mut node = SgfNode::new([
+            SgfProp::new_many("L", vec!["aa", "bb"]),
+            SgfProp::new("LB", "cc:X"),
+            SgfProp::new("M", "dd"),
+            SgfProp::new("L", "ee"),
+        ]);
+
+        node.upgrade_ff3();
+
+        assert_eq!(node.to_string(), ";LB[cc:X][aa:a][bb:b][ee:c]MA[dd]");
+
+        let points: Vec<String> = (0..54)
+            .map(|i| format!("{}a", (b'a' + i % 26) as char))
+            .collect();
+        let mut node = SgfNode::new([SgfProp {
+            id: String::from("L"),
+            values: points,
+        }]);
+
+        node.upgrade_ff3();
+
+        let labels = &node.props[0].values;
+
+        assert_eq!(labels[25], "za:z");
+        assert_eq!(labels[26], "aa:A");
+        assert_eq!(labels[51], "za:Z");
+        assert_eq!(labels[52], "aa:53");
+    }
+
+    #[test]
+    fn format_escaped_prop() {
+        let prop = SgfProp::new_text("C", r"a]b\c");
+
+        assert_eq!(prop.to_string(), r"C[a\]b\\c]");
+    }
+
     #[test]
     fn format_node() {
         let node = SgfNode {
@@ -160,4 +320,14 @@ mod tests {
             String::from("(;AB[cd][ef]AW[aa][bb];B[qq](;W[aq])(;W[bq]))")
         )
     }
+
+    #[test]
+    fn format_collection() {
+        let collection = Collection::new([
+            SgfTree::new([SgfNode::new([SgfProp::new("B", "aa")])], []),
+            SgfTree::new([SgfNode::new([SgfProp::new("W", "bb")])], []),
+        ]);
+
+        assert_eq!(collection.to_string(), "(;B[aa])\n(;W[bb])\n");
+    }
 }
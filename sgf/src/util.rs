@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::{ParseError, ParseResult};
 
 /// Parse a board coordinate, such as those contained within B[] and W[] properties
@@ -31,20 +33,157 @@ fn parse_coord(c: char) -> ParseResult<usize> {
     Ok(u - 39)
 }
 
+/// Parse the value of a B[] or W[] property on a board of the given (width, height).
+/// Both the empty value and, on boards up to 19x19, `tt` are a pass, returned as [None].
+pub fn parse_move(value: &str, size: (usize, usize)) -> ParseResult<Option<(usize, usize)>> {
+    if value.is_empty() || (value == "tt" && size.0 <= 19 && size.1 <= 19) {
+        return Ok(None);
+    }
+
+    Ok(Some(parse_coords(value)?))
+}
+
+/// Split a composed value, such as `aa:cc` or `19:13`, at its first `:` that isn't escaped.
+/// Both parts keep their escapes.
+pub fn parse_composed(value: &str) -> ParseResult<(&str, &str)> {
+    let mut escaped = false;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            ':' if !escaped => return Ok((&value[..i], &value[i + 1..])),
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+
+    Err(ParseError::InvalidValue(String::from(value)))
+}
+
+/// Parse the values of a point list property such as AB[].
+/// A compressed value `aa:cc` stands for every point of the rectangle from aa to cc,
+/// and a single empty value is an empty list. Points listed more than once are kept once.
+pub fn parse_point_list<S: AsRef<str>>(values: &[S]) -> ParseResult<Vec<(usize, usize)>> {
+    if let [v] = values {
+        if v.as_ref().is_empty() {
+            return Ok(Vec::new());
+        }
+    }
+
+    let mut points = Vec::new();
+    let mut seen = HashSet::new();
+
+    for v in values {
+        let v = v.as_ref();
+
+        let (a, b) = match parse_composed(v) {
+            Ok((a, b)) => (parse_coords(a)?, parse_coords(b)?),
+            Err(_) => (parse_coords(v)?, parse_coords(v)?),
+        };
+
+        for y in a.1.min(b.1)..=a.1.max(b.1) {
+            for x in a.0.min(b.0)..=a.0.max(b.0) {
+                if seen.insert((x, y)) {
+                    points.push((x, y));
+                }
+            }
+        }
+    }
+
+    return Ok(points);
+}
+
+/// Write a point list with rectangles of points compressed to `aa:cc` values.
+/// The reverse of [parse_point_list], so the empty list is a single empty value.
+pub fn compress_points(points: &[(usize, usize)]) -> Vec<String> {
+    if points.is_empty() {
+        return vec![String::new()];
+    }
+
+    let mut remaining: Vec<(usize, usize)> = points.to_vec();
+    remaining.sort_by_key(|&(x, y)| (y, x));
+    remaining.dedup();
+
+    let mut values = Vec::new();
+
+    while let Some(&(x0, y0)) = remaining.first() {
+        let has = |p: &(usize, usize), remaining: &[(usize, usize)]| {
+            remaining
+                .binary_search_by_key(&(p.1, p.0), |&(x, y)| (y, x))
+                .is_ok()
+        };
+
+        // Grow the rectangle right as far as possible, then down while whole rows fit.
+        let mut x1 = x0;
+        while has(&(x1 + 1, y0), &remaining) {
+            x1 += 1;
+        }
+
+        let mut y1 = y0;
+        while (x0..=x1).all(|x| has(&(x, y1 + 1), &remaining)) {
+            y1 += 1;
+        }
+
+        remaining.retain(|&(x, y)| !(x0..=x1).contains(&x) || !(y0..=y1).contains(&y));
+
+        if (x0, y0) == (x1, y1) {
+            values.push(format_coords((x0, y0)));
+        } else {
+            values.push(format!(
+                "{}:{}",
+                format_coords((x0, y0)),
+                format_coords((x1, y1))
+            ));
+        }
+    }
+
+    return values;
+}
+
+/// Format a board coordinate for B[] and W[] properties. The reverse of [parse_coords].
+///
+/// # Panics
+/// If either coordinate is 52 or more, since sgf has no letter for it.
+pub fn format_coords(p: (usize, usize)) -> String {
+    [format_coord(p.0), format_coord(p.1)].iter().collect()
+}
+
+fn format_coord(u: usize) -> char {
+    assert!(u < 52, "sgf coordinates only go up to 51, got {u}");
+
+    if u < 26 {
+        return (u as u8 + 97) as char;
+    }
+
+    (u as u8 + 39) as char
+}
+
+/// Join the two parts of a composed value with `:`, escaping them with [escape]
+/// and escaping any `:` in them. The reverse of [parse_composed] followed by [unescape].
+pub fn format_composed(a: &str, b: &str) -> String {
+    let part = |s: &str| escape(s).replace(':', "\\:");
+
+    format!("{}:{}", part(a), part(b))
+}
+
+/// Format a (width, height) for a SZ[] property.
+pub fn format_board_size(size: (usize, usize)) -> String {
+    if size.0 == size.1 {
+        return size.0.to_string();
+    }
+
+    format!("{}:{}", size.0, size.1)
+}
+
 /// Return the (width, height) in a SZ[] property.
 pub fn parse_board_size(s: &str) -> ParseResult<(usize, usize)> {
-    match s.find(':') {
-        None => {
+    match parse_composed(s) {
+        Err(_) => {
             let w = s.parse::<usize>()?;
 
             return Ok((w, w));
         }
 
-        Some(x) => {
-            let (w_s, h_s) = s.split_at(x);
-
-            let h_s = h_s.trim_start_matches(':');
-
+        Ok((w_s, h_s)) => {
             let w = w_s.parse::<usize>()?;
             let h = h_s.parse::<usize>()?;
 
@@ -53,10 +192,72 @@ pub fn parse_board_size(s: &str) -> ParseResult<(usize, usize)> {
     }
 }
 
+/// Normalise a property id from an old (FF[3] or earlier) file to all capital letters.
+/// Mixed-case ids like `AddBlack` keep only their capitals, and all-lowercase ids are capitalized.
+pub fn normalize_id(id: &str) -> String {
+    match id.chars().any(|c| c.is_ascii_uppercase()) {
+        true => id.chars().filter(|c| c.is_ascii_uppercase()).collect(),
+        false => id.to_ascii_uppercase(),
+    }
+}
+
+/// Remove the escapes from a property value as written in an sgf file:
+/// `\` keeps the next character as it is, and `\` before a line break removes both (a soft line break).
+pub fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // A soft line break may be any of \n, \r, \n\r or \r\n.
+            Some(n @ ('\n' | '\r')) => {
+                let pair = if n == '\n' { '\r' } else { '\n' };
+
+                if chars.peek() == Some(&pair) {
+                    chars.next();
+                }
+            }
+            Some(n) => out.push(n),
+            None => {}
+        }
+    }
+
+    return out;
+}
+
+/// Escape `]` and `\` in a property value so that it can be written in an sgf file.
+/// The reverse of [unescape].
+pub fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if c == ']' || c == '\\' {
+            out.push('\\');
+        }
+
+        out.push(c);
+    }
+
+    return out;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn normalize_ids() {
+        assert_eq!(normalize_id("AB"), "AB");
+        assert_eq!(normalize_id("AddBlack"), "AB");
+        assert_eq!(normalize_id("Comment"), "C");
+        assert_eq!(normalize_id("sz"), "SZ");
+    }
+
     #[test]
     fn coords() {
         assert_eq!(parse_coords("aa").unwrap(), (0, 0));
@@ -64,6 +265,18 @@ mod tests {
         assert_eq!(parse_coords("dE").unwrap(), (3, 30));
     }
 
+    #[test]
+    fn format() {
+        assert_eq!(format_coords((0, 0)), "aa");
+        assert_eq!(format_coords((51, 51)), "ZZ");
+        assert_eq!(format_coords((3, 30)), "dE");
+
+        assert!(std::panic::catch_unwind(|| format_coords((52, 0))).is_err());
+
+        assert_eq!(format_board_size((19, 19)), "19");
+        assert_eq!(format_board_size((5, 9)), "5:9");
+    }
+
     #[test]
     fn wrong_coords() {
         assert_eq!(parse_coords("00"), Err(ParseError::CoordinateParseError));
@@ -71,9 +284,102 @@ mod tests {
         assert_eq!(parse_coords(""), Err(ParseError::CoordinateParseError));
     }
 
+    #[test]
+    fn escapes() {
+        assert_eq!(unescape(r"a\]b"), "a]b");
+        assert_eq!(unescape(r"a\\b"), r"a\b");
+        assert_eq!(unescape(r"\a\:b"), "a:b");
+        assert_eq!(unescape("soft\\\nbreak\\\r\nhere"), "softbreakhere");
+        assert_eq!(unescape("hard\nbreak"), "hard\nbreak");
+
+        assert_eq!(escape(r"a]b\c"), r"a\]b\\c");
+        assert_eq!(unescape(&escape(r"]\[")), r"]\[");
+    }
+
+    #[test]
+    fn moves() {
+        assert_eq!(parse_move("", (19, 19)), Ok(None));
+        assert_eq!(parse_move("tt", (19, 19)), Ok(None));
+        assert_eq!(parse_move("tt", (21, 21)), Ok(Some((19, 19))));
+        assert_eq!(parse_move("cd", (9, 9)), Ok(Some((2, 3))));
+    }
+
+    #[test]
+    fn point_lists() {
+        assert_eq!(
+            parse_point_list(&["bb:aa", "dd"]),
+            Ok(vec![(0, 0), (1, 0), (0, 1), (1, 1), (3, 3)])
+        );
+        assert_eq!(parse_point_list(&[""]), Ok(Vec::new()));
+        assert_eq!(
+            parse_point_list(&["aa:bb", "aa", "bb:cb"]),
+            Ok(vec![(0, 0), (1, 0), (0, 1), (1, 1), (2, 1)])
+        );
+        assert_eq!(
+            parse_point_list(&["aa", ""]),
+            Err(ParseError::CoordinateParseError)
+        );
+        assert_eq!(
+            parse_point_list(&["aa:"]),
+            Err(ParseError::CoordinateParseError)
+        );
+    }
+
+    #[test]
+    fn compressed_point_lists() {
+        // a a a +
+        // a a a b
+        // + + + b
+        let points = [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (3, 2),
+        ];
+
+        let values = compress_points(&points);
+
+        assert_eq!(values, ["aa:cb", "db:dc"]);
+
+        let mut parsed = parse_point_list(&values).unwrap();
+        parsed.sort_by_key(|&(x, y)| (y, x));
+
+        assert_eq!(parsed, points);
+        assert_eq!(compress_points(&[(4, 4), (4, 4)]), ["ee"]);
+        assert_eq!(compress_points(&[]), [""]);
+        assert_eq!(parse_point_list(&compress_points(&[])), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn composed() {
+        assert_eq!(parse_composed("aa:cc"), Ok(("aa", "cc")));
+        assert_eq!(parse_composed(r"a\:b:1.0"), Ok((r"a\:b", "1.0")));
+        assert_eq!(parse_composed(r"a\\:b"), Ok((r"a\\", "b")));
+        assert!(parse_composed(r"a\:b").is_err());
+    }
+
+    #[test]
+    fn format_composed_values() {
+        assert_eq!(format_composed("a:b", "1.0"), r"a\:b:1.0");
+        assert_eq!(format_composed(r"c\", "d]"), r"c\\:d\]");
+
+        let value = format_composed("x:y", "z");
+        let (a, b) = parse_composed(&value).unwrap();
+
+        assert_eq!(
+            (unescape(a), unescape(b)),
+            (String::from("x:y"), String::from("z"))
+        );
+    }
+
     #[test]
     pub fn board_size() {
         assert_eq!(parse_board_size("19"), Ok((19, 19)));
         assert_eq!(parse_board_size("5:9"), Ok((5, 9)));
+        assert!(parse_board_size("5:").is_err());
     }
 }
@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+
+use mb_goban::{Board, Group, Stone};
+
+/// The points orthogonally next to `p` on a board of the given (width, height).
+pub(crate) fn neighbors(size: (usize, usize), p: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut n = Vec::with_capacity(4);
+
+    if p.0 > 0 {
+        n.push((p.0 - 1, p.1));
+    }
+    if p.1 > 0 {
+        n.push((p.0, p.1 - 1));
+    }
+    if p.0 + 1 < size.0 {
+        n.push((p.0 + 1, p.1));
+    }
+    if p.1 + 1 < size.1 {
+        n.push((p.0, p.1 + 1));
+    }
+
+    return n;
+}
+
+/// A maximal connected set of points that are not the color being checked.
+struct Region {
+    empty: Vec<(usize, usize)>,
+    /// Indices of the chains bordering the region.
+    chains: HashSet<usize>,
+}
+
+/// Find every stone of `color` that can never be captured, even if `color` only ever passes,
+/// using Benson's algorithm.
+pub fn unconditionally_alive(board: &Board, color: Stone) -> HashSet<(usize, usize)> {
+    let (w, h) = board.size();
+    let points = (0..h).flat_map(|y| (0..w).map(move |x| (x, y)));
+
+    let mut chains: Vec<Group> = Vec::new();
+    let mut chain_of = vec![usize::MAX; w * h];
+
+    for (x, y) in points.clone() {
+        if board.get(x, y) != Ok(color) || chain_of[y * w + x] != usize::MAX {
+            continue;
+        }
+
+        let chain = board.get_group(x, y).unwrap();
+
+        for p in &chain.points {
+            chain_of[p.1 * w + p.0] = chains.len();
+        }
+
+        chains.push(chain);
+    }
+
+    let mut regions: Vec<Region> = Vec::new();
+    let mut visited = vec![false; w * h];
+
+    for (x, y) in points {
+        if board.get(x, y) == Ok(color) || visited[y * w + x] {
+            continue;
+        }
+
+        let mut region = Region {
+            empty: Vec::new(),
+            chains: HashSet::new(),
+        };
+
+        let mut stack = vec![(x, y)];
+        visited[y * w + x] = true;
+
+        while let Some(p) = stack.pop() {
+            if board.get(p.0, p.1) == Ok(Stone::Empty) {
+                region.empty.push(p);
+            }
+
+            for n in neighbors((w, h), p) {
+                let i = n.1 * w + n.0;
+
+                if board.get(n.0, n.1) == Ok(color) {
+                    region.chains.insert(chain_of[i]);
+                } else if !visited[i] {
+                    visited[i] = true;
+                    stack.push(n);
+                }
+            }
+        }
+
+        regions.push(region);
+    }
+
+    let mut alive: Vec<bool> = vec![true; chains.len()];
+    let mut healthy: Vec<bool> = vec![true; regions.len()];
+
+    loop {
+        let mut changed = false;
+
+        for (c, chain) in chains.iter().enumerate() {
+            if !alive[c] {
+                continue;
+            }
+
+            // A region is vital to a chain if all of its empty points are liberties of the chain.
+            let vital = regions
+                .iter()
+                .zip(&healthy)
+                .filter(|(r, &h)| h && r.chains.contains(&c))
+                .filter(|(r, _)| r.empty.iter().all(|p| chain.liberties.contains(p)))
+                .count();
+
+            if vital < 2 {
+                alive[c] = false;
+                changed = true;
+            }
+        }
+
+        for (r, region) in regions.iter().enumerate() {
+            if healthy[r] && region.chains.iter().any(|&c| !alive[c]) {
+                healthy[r] = false;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    return chains
+        .iter()
+        .zip(alive)
+        .filter(|(_, a)| *a)
+        .flat_map(|(c, _)| c.points.iter().copied())
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::board_from;
+
+    #[test]
+    fn two_eyes() {
+        let board = board_from(&[
+            "+b+b+++", //
+            "bbbb+++", //
+            "+++++++", //
+        ]);
+
+        assert_eq!(unconditionally_alive(&board, Stone::Black).len(), 6);
+    }
+
+    #[test]
+    fn one_eye() {
+        let board = board_from(&[
+            "++b++++", //
+            "bbb++++", //
+            "+++++++", //
+        ]);
+
+        assert!(unconditionally_alive(&board, Stone::Black).is_empty());
+    }
+
+    #[test]
+    fn eye_with_enemy_stone() {
+        // White can never fill the point next to its own stone, so the top left is still an eye.
+        let board = board_from(&[
+            "w+b+b++", //
+            "bbbbb++", //
+            "+++++++", //
+        ]);
+
+        assert_eq!(unconditionally_alive(&board, Stone::Black).len(), 7);
+    }
+}
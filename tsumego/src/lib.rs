@@ -0,0 +1,31 @@
+#![allow(clippy::needless_return)]
+
+mod benson;
+mod solver;
+
+pub use benson::unconditionally_alive;
+pub use solver::{Move, Problem, Solution, Status};
+
+#[cfg(test)]
+mod tests {
+    use mb_goban::{Board, Rules, Stone};
+
+    /// Build a board from rows of `b`, `w` and `+`.
+    pub fn board_from(rows: &[&str]) -> Board {
+        let mut board = Board::empty(rows[0].len(), rows.len());
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let stone = match c {
+                    'b' => Stone::Black,
+                    'w' => Stone::White,
+                    _ => continue,
+                };
+
+                board.place(x, y, stone, &Rules::JAPANESE).unwrap();
+            }
+        }
+
+        board
+    }
+}
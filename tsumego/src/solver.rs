@@ -0,0 +1,439 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use mb_goban::{Board, Error, IllegalMove, Rules, Stone};
+
+use crate::benson::unconditionally_alive;
+
+/// A move considered by the solver.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Move {
+    Pass,
+    /// Zero-indexed, starting from the top-left, like [Board].
+    Play(usize, usize),
+}
+
+/// The life and death status of a [Problem]'s target group.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Status {
+    /// The target can be captured.
+    Kill,
+    /// The target can not be captured.
+    Live,
+    /// The target can be captured only if the attacker wins a ko.
+    Ko,
+    /// The search ran out of depth before finding an answer.
+    Unknown,
+}
+
+/// The answer to a [Problem].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Solution {
+    pub status: Status,
+    /// Best play for both sides from the problem position.
+    /// For [Status::Ko], this is the line where the attacker wins the ko.
+    pub main_line: Vec<Move>,
+}
+
+/// A life and death problem: can the group at `target` be captured?
+///
+/// The owner of `target` is the defender and the other color is the attacker.
+/// The attacker wins by capturing the target. The defender wins by making it
+/// [unconditionally alive](crate::unconditionally_alive), or when neither side has a useful move left,
+/// which includes seki.
+pub struct Problem {
+    pub board: Board,
+    /// The points the players may play on. Every other point is left alone.
+    pub region: Vec<(usize, usize)>,
+    pub target: (usize, usize),
+    pub to_move: Stone,
+    pub rules: Rules,
+    /// The maximum number of moves, passes included, to look ahead.
+    pub max_depth: usize,
+}
+impl Problem {
+    pub fn new(
+        board: Board,
+        region: Vec<(usize, usize)>,
+        target: (usize, usize),
+        to_move: Stone,
+    ) -> Self {
+        Self {
+            board,
+            region,
+            target,
+            to_move,
+            rules: Rules::JAPANESE,
+            max_depth: 20,
+        }
+    }
+
+    /// Solve the problem with an alpha-beta search.
+    ///
+    /// Kos are handled by solving twice: once where the defender wins every ko,
+    /// and once where the attacker does. A side that wins kos may retake them immediately,
+    /// as if it always had a ko threat.
+    pub fn solve(&self) -> Solution {
+        let defender = match self.board.get(self.target.0, self.target.1) {
+            Ok(Stone::Empty) | Err(_) => {
+                return Solution {
+                    status: Status::Kill,
+                    main_line: Vec::new(),
+                };
+            }
+            Ok(s) => s,
+        };
+
+        let mut defender_wins_kos = Search::new(self, defender, defender);
+        let (outcome, line) = defender_wins_kos.solve();
+
+        if outcome == Outcome::Kill {
+            return Solution {
+                status: Status::Kill,
+                main_line: line,
+            };
+        }
+
+        let mut attacker_wins_kos = Search::new(self, defender, !defender);
+        let (ko_outcome, ko_line) = attacker_wins_kos.solve();
+
+        let status = match (outcome, ko_outcome) {
+            (_, Outcome::Live) => Status::Live,
+            (Outcome::Live, Outcome::Kill) => Status::Ko,
+
+            _ => Status::Unknown,
+        };
+
+        let main_line = match status {
+            Status::Ko => ko_line,
+            _ => line,
+        };
+
+        Solution { status, main_line }
+    }
+
+    /// Check an answer to the problem: whether playing `m` keeps the best [Status]
+    /// the side to move can get.
+    pub fn is_correct(&self, m: Move) -> bool {
+        let best = self.solve().status;
+
+        if best == Status::Unknown {
+            return false;
+        }
+
+        let mut board = self.board.clone();
+
+        if let Move::Play(x, y) = m {
+            if board.play(x, y, self.to_move, &self.rules).is_err() {
+                return false;
+            }
+        }
+
+        let next = Problem {
+            board,
+            region: self.region.clone(),
+            target: self.target,
+            to_move: !self.to_move,
+            rules: self.rules,
+            max_depth: self.max_depth.saturating_sub(1),
+        };
+
+        return next.solve().status == best;
+    }
+}
+
+/// The result of searching a position, from the attacker's point of view.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Outcome {
+    Kill,
+    Live,
+    Unknown,
+}
+
+/// A position that has been searched, and the depth that was left when it was.
+struct Entry {
+    outcome: Outcome,
+    best: Option<Move>,
+    depth: usize,
+}
+
+struct Search<'a> {
+    problem: &'a Problem,
+    defender: Stone,
+    ko_winner: Stone,
+
+    table: HashMap<u64, Entry>,
+}
+impl<'a> Search<'a> {
+    fn new(problem: &'a Problem, defender: Stone, ko_winner: Stone) -> Self {
+        Self {
+            problem,
+            defender,
+            ko_winner,
+
+            table: HashMap::new(),
+        }
+    }
+
+    /// Search from the problem position and follow the best moves to build the main line.
+    fn solve(&mut self) -> (Outcome, Vec<Move>) {
+        let problem = self.problem;
+
+        let mut board = problem.board.clone();
+        let mut turn = problem.to_move;
+        let mut previous = 0;
+
+        let outcome = self.search(&board, turn, previous, problem.max_depth);
+
+        let mut line = Vec::new();
+
+        for depth in (1..=problem.max_depth).rev() {
+            let key = self.key(&board, turn, previous);
+
+            let best = match self.table.get(&key).and_then(|e| e.best) {
+                Some(m) => m,
+                None => break,
+            };
+
+            if let Move::Play(x, y) = best {
+                // The table key doesn't hold the whole history, so a move stored for the same
+                // position reached another way may be illegal here. The line ends there.
+                let Some(next) = self.play(&board, x, y, turn) else {
+                    break;
+                };
+
+                previous = board.position_hash();
+                board = next;
+            }
+
+            turn = !turn;
+            line.push(best);
+
+            // Make sure the next position was searched with the depth it has now.
+            self.search(&board, turn, previous, depth - 1);
+        }
+
+        return (outcome, line);
+    }
+
+    /// `previous` is the hash of the position before the last move, which matters for the ko rule.
+    fn search(&mut self, board: &Board, turn: Stone, previous: u64, depth: usize) -> Outcome {
+        let (tx, ty) = self.problem.target;
+
+        if board.get(tx, ty) != Ok(self.defender) {
+            return Outcome::Kill;
+        }
+
+        if unconditionally_alive(board, self.defender).contains(&(tx, ty)) {
+            return Outcome::Live;
+        }
+
+        if depth == 0 {
+            return Outcome::Unknown;
+        }
+
+        let key = self.key(board, turn, previous);
+
+        if let Some(e) = self.table.get(&key) {
+            if e.outcome != Outcome::Unknown || e.depth >= depth {
+                return e.outcome;
+            }
+        }
+
+        let attacking = turn != self.defender;
+        let (win, loss) = match attacking {
+            true => (Outcome::Kill, Outcome::Live),
+            false => (Outcome::Live, Outcome::Kill),
+        };
+
+        let mut result = loss;
+        let mut best = None;
+
+        let hash = board.position_hash();
+
+        for m in self.moves(board, turn) {
+            let outcome = match m {
+                Move::Play(x, y) => match self.play(board, x, y, turn) {
+                    Some(next) => self.search(&next, !turn, hash, depth - 1),
+                    None => continue,
+                },
+                Move::Pass => self.search(board, !turn, previous, depth - 1),
+            };
+
+            if outcome == win {
+                result = win;
+                best = Some(m);
+                break;
+            }
+
+            if outcome == Outcome::Unknown || best.is_none() {
+                if outcome == Outcome::Unknown {
+                    result = Outcome::Unknown;
+                }
+
+                best = Some(m);
+            }
+        }
+
+        self.table.insert(
+            key,
+            Entry {
+                outcome: result,
+                best,
+                depth,
+            },
+        );
+
+        return result;
+    }
+
+    /// Candidate moves, with liberties of the target first.
+    /// Only the defender may pass: an attacker that passes has given up,
+    /// so an attacker with no moves left loses.
+    fn moves(&self, board: &Board, turn: Stone) -> Vec<Move> {
+        let (tx, ty) = self.problem.target;
+        let liberties = board
+            .get_group(tx, ty)
+            .map(|g| g.liberties)
+            .unwrap_or_default();
+
+        let mut moves: Vec<(usize, usize)> = self
+            .problem
+            .region
+            .iter()
+            .copied()
+            .filter(|&(x, y)| board.get(x, y) == Ok(Stone::Empty))
+            .collect();
+
+        moves.sort_by_key(|p| !liberties.contains(p));
+
+        let mut moves: Vec<Move> = moves.into_iter().map(|(x, y)| Move::Play(x, y)).collect();
+
+        if turn == self.defender {
+            moves.push(Move::Pass);
+        }
+
+        return moves;
+    }
+
+    /// Play a move, letting [Search::ko_winner] ignore the ko rules.
+    /// Returns [None] if the move is illegal.
+    fn play(&self, board: &Board, x: usize, y: usize, turn: Stone) -> Option<Board> {
+        let mut next = board.clone();
+
+        match next.play(x, y, turn, &self.problem.rules) {
+            Ok(_) => Some(next),
+
+            Err(Error::IllegalMove(IllegalMove::Ko | IllegalMove::SuperKo))
+                if turn == self.ko_winner =>
+            {
+                next.place(x, y, turn, &self.problem.rules).ok()?;
+
+                Some(next)
+            }
+
+            Err(_) => None,
+        }
+    }
+
+    fn key(&self, board: &Board, turn: Stone, previous: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        board.position_hash().hash(&mut hasher);
+        turn.hash(&mut hasher);
+        previous.hash(&mut hasher);
+
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::board_from;
+
+    /// Black has a straight three eye space in the corner.
+    fn straight_three(to_move: Stone) -> Problem {
+        let board = board_from(&[
+            "+++bw++", //
+            "bbbbw++", //
+            "wwwww++", //
+            "+++++++", //
+        ]);
+
+        Problem::new(board, vec![(0, 0), (1, 0), (2, 0)], (0, 1), to_move)
+    }
+
+    #[test]
+    fn defender_lives() {
+        let solution = straight_three(Stone::Black).solve();
+
+        assert_eq!(solution.status, Status::Live);
+        assert_eq!(solution.main_line, vec![Move::Play(1, 0)]);
+    }
+
+    #[test]
+    fn attacker_kills() {
+        let solution = straight_three(Stone::White).solve();
+
+        assert_eq!(solution.status, Status::Kill);
+        assert_eq!(solution.main_line[0], Move::Play(1, 0));
+    }
+
+    #[test]
+    fn check_answers() {
+        let problem = straight_three(Stone::White);
+
+        assert!(problem.is_correct(Move::Play(1, 0)));
+        assert!(!problem.is_correct(Move::Play(0, 0)));
+        assert!(!problem.is_correct(Move::Play(3, 0)));
+    }
+
+    #[test]
+    fn already_alive() {
+        let board = board_from(&[
+            "+b+bw++", //
+            "bbbbw++", //
+            "wwwww++", //
+            "+++++++", //
+        ]);
+
+        let solution = Problem::new(board, vec![(0, 0), (2, 0)], (0, 1), Stone::White).solve();
+
+        assert_eq!(solution.status, Status::Live);
+        assert!(solution.main_line.is_empty());
+    }
+
+    #[test]
+    fn ko() {
+        // Black can only make a second eye by taking the white stone,
+        // but white can take back.
+        let board = board_from(&[
+            "+bw+w++", //
+            "bbbww++", //
+            "wwww+++", //
+            "+++++++", //
+        ]);
+
+        let region = vec![(0, 0), (2, 0), (3, 0)];
+
+        let solution = Problem::new(board.clone(), region.clone(), (0, 1), Stone::Black).solve();
+
+        assert_eq!(solution.status, Status::Ko);
+        assert_eq!(solution.main_line[0], Move::Play(3, 0));
+
+        let solution = Problem::new(board, region, (0, 1), Stone::White).solve();
+
+        assert_eq!(solution.status, Status::Kill);
+        assert_eq!(solution.main_line, vec![Move::Play(0, 0)]);
+    }
+
+    #[test]
+    fn out_of_depth() {
+        let mut problem = straight_three(Stone::White);
+        problem.max_depth = 1;
+
+        assert_eq!(problem.solve().status, Status::Unknown);
+    }
+}
@@ -1,8 +1,7 @@
 use eframe::egui;
 use egui::{Color32, Response, Ui};
 
-use mb_goban::Board;
-use mb_goban::Rules;
+use mb_goban::Game;
 use mb_goban::Stone;
 
 #[derive(Clone, Copy)]
@@ -17,6 +16,8 @@ pub struct BoardStyle {
     pub stone_radius: f32,
     /// in egui screen units
     pub star_point_radius: f32,
+    /// The color of every stone when the [Variant](mb_goban::Variant) hides colors.
+    pub hidden_stone_color: Color32,
 }
 impl Default for BoardStyle {
     fn default() -> Self {
@@ -26,17 +27,20 @@ impl Default for BoardStyle {
             background_color: Color32::from_rgb(0xDE, 0xB8, 0x87),
             stone_radius: 0.46,
             star_point_radius: 5.0,
+            hidden_stone_color: Color32::GRAY,
         }
     }
 }
 
 pub fn render_board(
     ui: &mut Ui,
-    board: &mut Board,
+    game: &mut Game,
     size: egui::Vec2,
     style: &BoardStyle,
-    turn: &mut Stone,
 ) -> Response {
+    let board = game.board();
+    let hidden = game.variant().hides_colors();
+
     let (response, painter) = ui.allocate_painter(size, egui::Sense::drag());
 
     // draw background color
@@ -94,6 +98,9 @@ pub fn render_board(
             }
 
             match board.get(x, y) {
+                Ok(Stone::Black | Stone::White) if hidden => {
+                    painter.circle_filled(egui::pos2(x_pos, y_pos), r, style.hidden_stone_color);
+                }
                 Ok(Stone::Black) => {
                     painter.circle_filled(egui::pos2(x_pos, y_pos), r, Color32::BLACK);
                 }
@@ -116,11 +123,8 @@ pub fn render_board(
                 ((p.y - inner_y) / distance_y).round() as usize,
             );
 
-            let rules = Rules::JAPANESE;
-
-            if board.play(x, y, *turn, &rules).is_ok() {
-                *turn = !*turn;
-            }
+            // Illegal moves are ignored.
+            let _ = game.play(x, y);
         }
     }
 
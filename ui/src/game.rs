@@ -1,29 +1,104 @@
 use std::path::PathBuf;
 
 use mb_goban::Board;
+use mb_goban::Game;
+use mb_goban::Rules;
 use mb_goban::Stone;
+use mb_goban::Variant;
 
-use mb_sgf::SgfTree;
+use mb_sgf::util::{format_coords, parse_point_list};
+use mb_sgf::{GameInfo, Replay, SgfNode, SgfProp, SgfTree};
 
 use eframe::egui;
 use egui::Ui;
 
 pub enum OptionalGame {
-    Some(GameState),
+    Some(Box<GameState>),
     None(NewGameBuilder),
 }
 
 pub struct GameState {
-    pub board: Board,
-    pub turn: Stone,
+    pub game: Game,
 
+    /// The sgf file the game was loaded from, or an empty tree for a new game.
     pub tree: SgfTree,
     /// The path to the sgf file being edited; None if the sgf file has not been saved.
     pub sgf_path: Option<PathBuf>,
+    /// Why the last save failed, to show in the window.
+    pub save_error: Option<String>,
+
+    /// The [path](mb_sgf::Cursor) of the last node of the main line of `tree`.
+    node: Vec<usize>,
+    /// The number of moves of the game that are already in `tree`.
+    saved_moves: usize,
+}
+
+impl GameState {
+    fn new(game: Game, tree: SgfTree, sgf_path: Option<PathBuf>) -> Self {
+        let node = vec![0; tree.main_line().count().saturating_sub(1)];
+        let saved_moves = game.moves().len();
+
+        return Self {
+            game,
+            tree,
+            sgf_path,
+            save_error: None,
+            node,
+            saved_moves,
+        };
+    }
+
+    /// Write the game to its sgf file, asking where to save it if it doesn't have one yet.
+    ///
+    /// A new game is recorded with [record_game](mb_sgf::record_game). Otherwise the moves
+    /// made since loading are added to the end of the main line of the loaded tree,
+    /// so the rest of the file is kept as it was.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        if self.sgf_path.is_none() {
+            self.sgf_path = rfd::FileDialog::new()
+                .add_filter("sgf", &["sgf"])
+                .save_file();
+        }
+
+        let Some(path) = &self.sgf_path else {
+            return Ok(());
+        };
+
+        if self.tree.nodes.is_empty() {
+            let Some(tree) = mb_sgf::record_game(&self.game, &GameInfo::default()) else {
+                return Err(std::io::Error::other("the board is too big for sgf"));
+            };
+
+            self.node = vec![0; tree.main_line().count() - 1];
+            self.tree = tree;
+        } else {
+            for m in &self.game.moves()[self.saved_moves..] {
+                let id = match m.color {
+                    Stone::White => "W",
+                    _ => "B",
+                };
+
+                let value = m.point.map(format_coords).unwrap_or_default();
+                let mut node = SgfNode::new([SgfProp::new(id, &value)]);
+
+                if let Some(player) = &m.player {
+                    node.props.push(SgfProp::new_text("PN", player));
+                }
+
+                // The node is the end of the main line, so it always exists.
+                self.node = self.tree.add_child(&self.node, node).unwrap();
+            }
+        }
+
+        self.saved_moves = self.game.moves().len();
+
+        return std::fs::write(path, self.tree.to_string());
+    }
 }
 
 pub struct NewGameBuilder {
     pub size: (usize, usize),
+    pub variant: Variant,
     pub tree: SgfTree,
     pub sgf_path: Option<PathBuf>,
 }
@@ -53,7 +128,21 @@ impl NewGameBuilder {
 
                                         self.sgf_root_props();
 
-                                        return Some(self.build());
+                                        match self.load_game() {
+                                            Ok(game) => {
+                                                return Some(GameState::new(
+                                                    game,
+                                                    self.tree.clone(),
+                                                    self.sgf_path.clone(),
+                                                ))
+                                            }
+                                            Err(e) => {
+                                                ui.label(format!(
+                                                    "failed to replay sgf tree: {}",
+                                                    e
+                                                ));
+                                            }
+                                        }
                                     }
                                     Err(e) => {
                                         ui.label(format!("failed to parse sgf tree: {:?}", e));
@@ -75,6 +164,16 @@ impl NewGameBuilder {
                 ui.label("Height:");
                 ui.add(egui::Slider::new(&mut self.size.1, 5..=50));
 
+                ui.heading("Variant:");
+
+                egui::ComboBox::from_id_source("variant")
+                    .selected_text(self.variant.name())
+                    .show_ui(ui, |ui| {
+                        for v in Variant::ALL {
+                            ui.selectable_value(&mut self.variant, v, v.name());
+                        }
+                    });
+
                 if ui.button("Finish").clicked() {
                     return Some(self.build());
                 }
@@ -85,12 +184,65 @@ impl NewGameBuilder {
     }
 
     pub fn build(&self) -> GameState {
-        return GameState {
-            board: Board::empty(self.size.0, self.size.1),
-            turn: Stone::Black,
-            tree: SgfTree::default(),
-            sgf_path: None,
-        };
+        let board = Board::empty(self.size.0, self.size.1);
+
+        return GameState::new(
+            Game::new(board, Rules::JAPANESE, self.variant),
+            SgfTree::default(),
+            None,
+        );
+    }
+
+    /// Replay the main line of the loaded tree as a [Game], one node at a time:
+    /// the setup stones of every node are added to the game, and every move is played in it.
+    fn load_game(&self) -> Result<Game, String> {
+        let mut replay = Replay::new(Rules::JAPANESE);
+        let mut game = Game::new(replay.board().clone(), Rules::JAPANESE, self.variant);
+
+        for node in self.tree.main_line() {
+            let played = replay.moves().len();
+
+            replay.apply(node).map_err(|e| format!("{:?}", e))?;
+
+            // SZ[] is only allowed on an empty board, so nothing is lost.
+            if game.board().size() != replay.board().size() {
+                game = Game::new(replay.board().clone(), Rules::JAPANESE, self.variant);
+            }
+
+            let mut setup = Vec::new();
+
+            for prop in &node.props {
+                let color = match prop.id.as_str() {
+                    "AB" => Stone::Black,
+                    "AW" => Stone::White,
+                    "AE" => Stone::Empty,
+                    _ => continue,
+                };
+
+                // Replay has already parsed the points.
+                for p in parse_point_list(&prop.values).unwrap() {
+                    setup.push((p, color));
+                }
+            }
+
+            if !setup.is_empty() {
+                game.setup(&setup).map_err(|e| format!("{:?}", e))?;
+            }
+
+            for &(color, point) in &replay.moves()[played..] {
+                // Handicap games start with white, and records may have several moves in a row.
+                game.set_turn(color);
+
+                let played = match point {
+                    Some((x, y)) => game.play(x, y).map(|_| ()),
+                    None => game.pass(),
+                };
+
+                played.map_err(|e| format!("{:?}", e))?;
+            }
+        }
+
+        return Ok(game);
     }
 
     /// Edit properties of `Self` based on the props in the sgf root
@@ -112,6 +264,7 @@ impl Default for NewGameBuilder {
     fn default() -> Self {
         Self {
             size: (19, 19),
+            variant: Variant::default(),
             tree: SgfTree::default(),
             sgf_path: None,
         }
@@ -5,11 +5,13 @@ use eframe::egui;
 use eframe::App;
 use eframe::NativeOptions;
 
+use mb_goban::{GameResult, Stone};
+
 mod board;
 mod game;
 
 use board::{render_board, BoardStyle};
-use game::{NewGameBuilder, OptionalGame};
+use game::{GameState, NewGameBuilder, OptionalGame};
 
 fn main() {
     let ops = NativeOptions::default();
@@ -47,13 +49,60 @@ fn main_ui(ui: &mut egui::Ui, state: &mut State) {
         OptionalGame::Some(ref mut g) => {
             let size = egui::vec2(800.0, 800.0);
 
-            render_board(ui, &mut g.board, size, &state.style, &mut g.turn);
+            render_board(ui, &mut g.game, size, &state.style);
+
+            game_controls(ui, g);
         }
 
         OptionalGame::None(ref mut b) => {
             if let Some(g) = b.render(ui) {
-                state.game = OptionalGame::Some(g);
+                state.game = OptionalGame::Some(Box::new(g));
             }
         }
     }
 }
+
+fn game_controls(ui: &mut egui::Ui, state: &mut GameState) {
+    ui.horizontal(|ui| {
+        if ui.button("Save").clicked() {
+            state.save_error = state
+                .save()
+                .err()
+                .map(|e| format!("failed to save sgf file: {e}"));
+        }
+
+        if let Some(e) = &state.save_error {
+            ui.label(e);
+        }
+    });
+
+    let game = &mut state.game;
+
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "Captures: black {}, white {}",
+            game.captures(Stone::Black),
+            game.captures(Stone::White)
+        ));
+
+        if game.pass_allowed && ui.button("Pass").clicked() {
+            let _ = game.pass();
+        }
+
+        if ui.button("Resign").clicked() {
+            let _ = game.resign();
+        }
+    });
+
+    match game.result() {
+        Some(GameResult::Win { winner, reason }) => {
+            ui.label(format!("{:?} wins by {:?}", winner, reason));
+        }
+        Some(GameResult::Counting) => {
+            ui.label("Both players passed");
+        }
+        None => {
+            ui.label(format!("{:?} to play", game.turn()));
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+
+use mb_goban::{Board, Error, Group, IllegalMove, Rules, Stone};
+use wasm_bindgen::prelude::*;
+
+/// A [Stone], as seen from JavaScript.
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Color {
+    Empty = 0,
+    Black = 1,
+    White = 2,
+}
+impl From<Stone> for Color {
+    fn from(s: Stone) -> Self {
+        match s {
+            Stone::Empty => Self::Empty,
+            Stone::Black => Self::Black,
+            Stone::White => Self::White,
+        }
+    }
+}
+impl From<Color> for Stone {
+    fn from(c: Color) -> Self {
+        match c {
+            Color::Empty => Self::Empty,
+            Color::Black => Self::Black,
+            Color::White => Self::White,
+        }
+    }
+}
+
+/// A short description of an [Error], for JavaScript exceptions.
+pub(crate) fn describe(e: Error) -> &'static str {
+    match e {
+        Error::CoordinatesOutOfBounds => "coordinates out of bounds",
+        Error::IllegalMove(IllegalMove::NonEmptySpace) => "point is not empty",
+        Error::IllegalMove(IllegalMove::SuicidalMove) => "suicide",
+        Error::IllegalMove(IllegalMove::Ko) => "ko",
+        Error::IllegalMove(IllegalMove::SuperKo) => "superko",
+        Error::IllegalMove(IllegalMove::Pass) => "pass not allowed",
+        Error::NothingToUndo => "nothing to undo",
+        Error::GameOver => "game over",
+    }
+}
+
+fn flatten(points: &HashSet<(usize, usize)>) -> Vec<u32> {
+    let mut points: Vec<_> = points.iter().copied().collect();
+    points.sort_by_key(|p| (p.1, p.0));
+
+    points
+        .into_iter()
+        .flat_map(|(x, y)| [x as u32, y as u32])
+        .collect()
+}
+
+/// A [Board] and the [Rules] moves are played with.
+#[wasm_bindgen(js_name = Board)]
+#[derive(Clone)]
+pub struct JsBoard {
+    board: Board,
+    rules: Rules,
+}
+#[wasm_bindgen(js_class = Board)]
+impl JsBoard {
+    /// An empty board played with [Rules::JAPANESE].
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            board: Board::empty(width, height),
+            rules: Rules::JAPANESE,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.board.size().0
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.board.size().1
+    }
+
+    #[wasm_bindgen(getter, js_name = suicideAllowed)]
+    pub fn suicide_allowed(&self) -> bool {
+        self.rules.suicide_allowed
+    }
+
+    #[wasm_bindgen(setter, js_name = suicideAllowed)]
+    pub fn set_suicide_allowed(&mut self, allowed: bool) {
+        self.rules.suicide_allowed = allowed;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn superko(&self) -> bool {
+        self.rules.superko
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_superko(&mut self, superko: bool) {
+        self.rules.superko = superko;
+    }
+
+    /// The [Color] at (x, y), or [Color::Empty] outside the board.
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        self.board.get(x, y).unwrap_or(Stone::Empty).into()
+    }
+
+    /// Play a move, returning the number of stones it captured.
+    /// Throws if the move is illegal.
+    pub fn play(&mut self, x: usize, y: usize, color: Color) -> Result<u32, JsError> {
+        let response = self
+            .board
+            .play(x, y, color.into(), &self.rules)
+            .map_err(|e| JsError::new(describe(e)))?;
+
+        return Ok((response.black_captures + response.white_captures) as u32);
+    }
+
+    /// Put a stone on the board, or remove one with [Color::Empty], ignoring the ko rules.
+    pub fn place(&mut self, x: usize, y: usize, color: Color) -> Result<(), JsError> {
+        self.board
+            .place(x, y, color.into(), &self.rules)
+            .map_err(|e| JsError::new(describe(e)))?;
+
+        Ok(())
+    }
+
+    /// Whether `color` could play at (x, y), without playing.
+    #[wasm_bindgen(js_name = isLegal)]
+    pub fn is_legal(&self, x: usize, y: usize, color: Color) -> bool {
+        self.board
+            .clone()
+            .play(x, y, color.into(), &self.rules)
+            .is_ok()
+    }
+
+    pub fn undo(&mut self) -> Result<(), JsError> {
+        self.board.undo().map_err(|e| JsError::new(describe(e)))
+    }
+
+    /// The group containing (x, y). Throws if (x, y) is not on the board.
+    pub fn group(&self, x: usize, y: usize) -> Result<JsGroup, JsError> {
+        let group = self
+            .board
+            .get_group(x, y)
+            .map_err(|e| JsError::new(describe(e)))?;
+
+        Ok(group.into())
+    }
+
+    /// The Tromp-Taylor area of each color, as `[black, white]`.
+    #[wasm_bindgen(js_name = areaScore)]
+    pub fn area_score(&self) -> Vec<u32> {
+        let (black, white) = self.board.area_score();
+
+        vec![black as u32, white as u32]
+    }
+
+    #[wasm_bindgen(js_name = starPoints)]
+    pub fn star_points(&self) -> Vec<u32> {
+        flatten(&self.board.star_points().into_iter().collect())
+    }
+}
+
+/// A [Group], with its points as flat arrays of coordinates sorted row by row.
+#[wasm_bindgen(js_name = Group)]
+pub struct JsGroup {
+    color: Color,
+    points: Vec<u32>,
+    liberties: Vec<u32>,
+    enemy_neighbors: Vec<u32>,
+}
+#[wasm_bindgen(js_class = Group)]
+impl JsGroup {
+    #[wasm_bindgen(getter)]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn points(&self) -> Vec<u32> {
+        self.points.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn liberties(&self) -> Vec<u32> {
+        self.liberties.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = enemyNeighbors)]
+    pub fn enemy_neighbors(&self) -> Vec<u32> {
+        self.enemy_neighbors.clone()
+    }
+}
+impl From<Group> for JsGroup {
+    fn from(g: Group) -> Self {
+        Self {
+            color: g.color.into(),
+            points: flatten(&g.points),
+            liberties: flatten(&g.liberties),
+            enemy_neighbors: flatten(&g.enemy_neighbors),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+#![allow(clippy::needless_return)]
+
+//! WebAssembly bindings for [mb_goban] and [mb_sgf], built with wasm-bindgen.
+//!
+//! Points are passed to JavaScript as flat arrays of coordinates: `[x0, y0, x1, y1, ...]`.
+
+mod board;
+mod sgf;
+
+pub use board::{Color, JsBoard, JsGroup};
+pub use sgf::{parse_sgf, JsSgfTree};
@@ -0,0 +1,71 @@
+use mb_sgf::util::{escape, unescape};
+use mb_sgf::{SgfProp, SgfTree};
+use wasm_bindgen::prelude::*;
+
+/// A parsed [SgfTree].
+#[wasm_bindgen(js_name = SgfTree)]
+#[derive(Clone)]
+pub struct JsSgfTree {
+    tree: SgfTree,
+}
+#[wasm_bindgen(js_class = SgfTree)]
+impl JsSgfTree {
+    /// The number of nodes in the main line.
+    #[wasm_bindgen(js_name = mainLineLength)]
+    pub fn main_line_length(&self) -> usize {
+        let mut len = 0;
+        let mut tree = Some(&self.tree);
+
+        while let Some(t) = tree {
+            len += t.nodes.len();
+            tree = t.children.first();
+        }
+
+        return len;
+    }
+
+    /// The unescaped values of property `id` on the root node, or an empty array if it has none.
+    #[wasm_bindgen(js_name = rootProperty)]
+    pub fn root_property(&self, id: &str) -> Vec<String> {
+        self.tree
+            .nodes
+            .first()
+            .and_then(|n| n.props.iter().find(|p| p.id == id))
+            .map(|p| p.values.iter().map(|v| unescape(v)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Set property `id` on the root node, replacing any values it had.
+    /// The values are escaped for sgf.
+    #[wasm_bindgen(js_name = setRootProperty)]
+    pub fn set_root_property(&mut self, id: &str, values: Vec<String>) {
+        if self.tree.nodes.is_empty() {
+            self.tree.nodes.push(Default::default());
+        }
+
+        let root = &mut self.tree.nodes[0];
+        let prop = SgfProp {
+            id: id.into(),
+            values: values.iter().map(|v| escape(v)).collect(),
+        };
+
+        match root.props.iter_mut().find(|p| p.id == id) {
+            Some(p) => *p = prop,
+            None => root.props.push(prop),
+        }
+    }
+
+    /// Serialize the tree back to SGF.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_sgf(&self) -> String {
+        self.tree.to_string()
+    }
+}
+
+/// Parse SGF text. Throws if it is not valid SGF.
+#[wasm_bindgen(js_name = parseSgf)]
+pub fn parse_sgf(text: &str) -> Result<JsSgfTree, JsError> {
+    let tree = mb_sgf::parse(text).map_err(|e| JsError::new(&format!("{e:?}")))?;
+
+    Ok(JsSgfTree { tree })
+}
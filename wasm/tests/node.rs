@@ -0,0 +1,58 @@
+//! Run from the `wasm` directory with `cargo test --target wasm32-unknown-unknown`,
+//! which needs `wasm-bindgen-test-runner` from wasm-bindgen-cli and node.
+#![cfg(target_arch = "wasm32")]
+
+use mb_wasm::{parse_sgf, Color, JsBoard};
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn capture() {
+    let mut board = JsBoard::new(5, 5);
+
+    board.play(1, 0, Color::White).unwrap();
+    board.play(0, 0, Color::Black).unwrap();
+    board.play(2, 0, Color::Black).unwrap();
+
+    assert_eq!(board.play(1, 1, Color::Black).unwrap(), 1);
+    assert_eq!(board.get(1, 0), Color::Empty);
+}
+
+#[wasm_bindgen_test]
+fn legality() {
+    let mut board = JsBoard::new(5, 5);
+
+    board.play(2, 2, Color::Black).unwrap();
+
+    assert!(!board.is_legal(2, 2, Color::White));
+    assert!(board.is_legal(3, 3, Color::White));
+    assert!(board.play(2, 2, Color::White).is_err());
+    assert!(board.play(9, 9, Color::White).is_err());
+}
+
+#[wasm_bindgen_test]
+fn group_and_score() {
+    let mut board = JsBoard::new(5, 5);
+
+    board.play(0, 0, Color::Black).unwrap();
+    board.play(1, 0, Color::Black).unwrap();
+
+    let group = board.group(0, 0).unwrap();
+
+    assert_eq!(group.color(), Color::Black);
+    assert_eq!(group.points(), vec![0, 0, 1, 0]);
+    assert_eq!(group.liberties(), vec![2, 0, 0, 1, 1, 1]);
+    assert_eq!(board.area_score(), vec![25, 0]);
+}
+
+#[wasm_bindgen_test]
+fn sgf_round_trip() {
+    let mut tree = parse_sgf("(;SZ[9];B[aa](;W[bb])(;W[cc]))").unwrap();
+
+    assert_eq!(tree.root_property("SZ"), vec![String::from("9")]);
+    assert_eq!(tree.main_line_length(), 3);
+
+    tree.set_root_property("PB", vec![String::from("Ann")]);
+
+    assert_eq!(tree.to_sgf(), "(;SZ[9]PB[Ann];B[aa](;W[bb])(;W[cc]))");
+    assert!(parse_sgf("(;B[aa]").is_err());
+}